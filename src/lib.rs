@@ -1,5 +1,8 @@
 pub mod sdk;
 
 pub use sdk::departments::DepartmentLookup;
+pub use sdk::error::{CacheError, ConfigError, Error, ScrapeError};
 pub use sdk::routing::cache::GeoCache;
+pub use sdk::routing::error::{GeocodeError, RoutingError};
+pub use sdk::routing::origin::{Origin, ResolvedOrigin, resolve_origin};
 pub use sdk::routing::route::{RouteSummary, get_road_distance};
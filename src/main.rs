@@ -1,125 +1,1084 @@
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate, NaiveTime};
 use clap::Parser;
 use ffe_rust::{
-    sdk::config::OrsConfig,
+    sdk::carpool::{GroupMember, filter_group_reachable_events},
+    sdk::client::FfeClient,
+    sdk::config::{AppConfig, OrsConfig},
+    sdk::daytrip::{TripFeasibility, assess_day_trips},
     sdk::departments::DepartmentLookup,
-    sdk::events::{filter_reachable_events, get_events_for_month},
+    sdk::details::{fetch_event_detail, refine_from_details, refine_rating_constraints},
+    sdk::eligibility::{eligibility_for, mark_eligibility},
+    sdk::events::{
+        Event, EventId, ReachabilityRules, filter_reachable_events, get_events_for_month,
+        get_events_for_range,
+    },
+    sdk::festival::{FestivalResults, group_festivals},
+    sdk::filters::SearchScope,
+    sdk::planner::{TourConstraints, TourObjective, plan_tour},
+    sdk::players::{
+        PlayerProfile, attach_field_stats, fetch_player_profile, fetch_registered_players,
+    },
+    sdk::results::{fetch_crosstable, fetch_standings, write_crosstable_csv, write_standings_csv},
     sdk::routing::{
-        HybridOrsProvider, LocalOrsProvider, cache::GeoCache, provider::RemoteOrsProvider,
+        Coverage, HybridOrsProvider, LayerKind, LocalOrsProvider, Origin, ProviderStack,
+        ResolvedOrigin, RoutingError,
+        cache::GeoCache,
+        event_coverage,
+        layer::ProviderMetrics,
+        provider::{OrsStatus, RemoteOrsProvider},
+        resolve_origin,
         service::RoutingProvider,
+        validate_geocodes,
     },
-    sdk::util::{log::init_logging, rate_limit::Limiter},
+    sdk::sync::EventSnapshot,
+    sdk::territory::road_connected_events,
+    sdk::time_control::TimeControl,
+    sdk::util::{log::init_logging, quota::QuotaTracker, rate_limit::Limiter, retry::RetryPolicy},
+};
+use std::{
+    error::Error,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
-use reqwest::blocking::Client as HttpClient;
-use std::{error::Error, fs::File, io::Write};
+
+const ORS_HOST: &str = "api.openrouteservice.org";
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 /// A CLI tool to find reachable FFE chess tournaments
+///
+/// Settings are read from `ffe-rust/config.toml` in the XDG config dirs (or `--config`).
+/// Environment variables override the file, and CLI flags override both.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(group = clap::ArgGroup::new("source").args(["origin", "city", "coords", "postal_code", "insee"]))]
 struct Cli {
-    /// The origin city name (e.g., "Rennes")
+    /// [Optional] Path to a TOML config file (overrides XDG lookup and FFE_CONFIG)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// [Optional] A named origin from the config file (e.g., "home")
     #[arg(short, long)]
-    city: String,
+    origin: Option<String>,
+
+    /// The origin city name (e.g., "Rennes")
+    #[arg(short, long, requires = "department")]
+    city: Option<String>,
 
     /// The 2-digit department code of the origin city (e.g., 35)
-    #[arg(short, long)]
-    department: String,
+    #[arg(short, long, requires = "city")]
+    department: Option<String>,
+
+    /// [Optional] Origin as raw coordinates "lat,lon" (e.g., "48.1173,-1.6778")
+    #[arg(long, allow_hyphen_values = true)]
+    coords: Option<String>,
+
+    /// [Optional] Origin as a French postal code; the department is inferred (e.g., 35500)
+    #[arg(long)]
+    postal_code: Option<String>,
+
+    /// [Optional] Origin as an INSEE commune code (e.g., 35360)
+    #[arg(long)]
+    insee: Option<String>,
 
     /// The month to search for events (1-12)
-    #[arg(short, long)]
-    month: u32,
+    #[arg(short, long, required_unless_present_any = ["plan_from", "validate_cache", "event", "standings", "crosstable"])]
+    month: Option<u32>,
+
+    /// [Optional] Maximum travel time in hours (default 1.5); with --day-trips, the longest
+    /// one-way drive instead of [day_trip] max_one_way_hours
+    #[arg(long)]
+    max_hours: Option<f64>,
+
+    /// [Optional] ORS routing profile (default "driving-car")
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// [Optional] Path of the geocode/route cache file
+    #[arg(long)]
+    cache: Option<PathBuf>,
+
+    /// [Optional] Path of the JSON output file
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// [Optional] Another traveller's origin for a group search, repeatable.
+    /// A named origin or coordinates/postal/INSEE spec, optionally with a limit: "club@1.0"
+    #[arg(long, allow_hyphen_values = true)]
+    member: Vec<String>,
+
+    /// [Optional] Classify events as day trip, overnight stay or infeasible
+    #[arg(long, conflicts_with = "member")]
+    day_trips: bool,
+
+    /// [Optional] Latest time to be back home for a day trip (HH:MM)
+    #[arg(long)]
+    latest_home: Option<NaiveTime>,
+
+    /// [Optional] Plan a tour of events from this date (YYYY-MM-DD) instead of a monthly search
+    #[arg(long, requires = "plan_to")]
+    plan_from: Option<NaiveDate>,
+
+    /// [Optional] Last date of the tour (YYYY-MM-DD)
+    #[arg(long, requires = "plan_from")]
+    plan_to: Option<NaiveDate>,
+
+    /// [Optional] Maximum number of events in the tour
+    #[arg(long, default_value_t = 3)]
+    max_events: usize,
+
+    /// [Optional] Free days required between two events of the tour
+    #[arg(long, default_value_t = 0)]
+    min_rest_days: i64,
+
+    /// [Optional] Drive directly between events at most this many days apart (default 6,
+    /// enough to chain a Sunday finish with the next Saturday's start)
+    #[arg(long, default_value_t = 6)]
+    chain_gap_days: i64,
+
+    /// [Optional] What the tour optimises: "travel" or "games"
+    #[arg(long, default_value = "travel")]
+    optimise: TourObjective,
+
+    /// [Optional] Candidate meeting town for a group search, repeatable (same syntax as --member)
+    #[arg(long, allow_hyphen_values = true)]
+    meeting_point: Vec<String>,
+
+    /// [Optional] Wait for a local ORS to finish building its graphs before searching
+    #[arg(long)]
+    wait_ready: bool,
+
+    /// [Optional] How long --wait-ready waits, in minutes
+    #[arg(long, default_value_t = 30)]
+    ready_timeout: u64,
+
+    /// [Optional] Write where each event lies relative to the local graph ([provider] coverage)
+    #[arg(long)]
+    coverage_report: Option<PathBuf>,
+
+    /// [Optional] Check every cached geocode against the department outlines ([paths] boundaries)
+    #[arg(long)]
+    validate_cache: bool,
+
+    /// [Optional] Only search these departments, repeatable (e.g., --in-department 35)
+    #[arg(long)]
+    in_department: Vec<String>,
+
+    /// [Optional] Only search the departments of this region, repeatable (e.g., "Bretagne")
+    #[arg(long)]
+    region: Vec<String>,
+
+    /// [Optional] Never search this department, repeatable (e.g., --exclude-department 75)
+    #[arg(long)]
+    exclude_department: Vec<String>,
+
+    /// [Optional] Only search departments at most this many borders from the origin's
+    #[arg(long)]
+    max_hops: Option<u32>,
+
+    /// [Optional] Only events with this time control, repeatable: classical, rapid, blitz or mixed
+    #[arg(long)]
+    time_control: Vec<TimeControl>,
+
+    /// [Optional] Keep events marked as cancelled ("ANNULÉ"), left out by default
+    #[arg(long)]
+    include_cancelled: bool,
+
+    /// [Optional] List every event in scope without routing, whatever the drive time
+    #[arg(long, conflicts_with_all = ["member", "day_trips", "plan_from"])]
+    ignore_travel_time: bool,
+
+    /// [Optional] Group tournaments held together (e.g. an open and its blitz) into festivals
+    #[arg(long, conflicts_with = "plan_from")]
+    festivals: bool,
+
+    /// [Optional] Write one event's detail page, by FFE Ref or link, instead of searching
+    #[arg(long, conflicts_with_all = ["validate_cache", "plan_from"])]
+    event: Option<EventId>,
+
+    /// [Optional] Write a past event's final standings, by FFE Ref or link (CSV if --output ends in .csv)
+    #[arg(long, conflicts_with_all = ["validate_cache", "plan_from", "event", "crosstable"])]
+    standings: Option<EventId>,
+
+    /// [Optional] Write a past event's crosstable, by FFE Ref or link (CSV if --output ends in .csv)
+    #[arg(long, conflicts_with_all = ["validate_cache", "plan_from", "event"])]
+    crosstable: Option<EventId>,
+
+    /// [Optional] With --standings or --crosstable, only the players of this club
+    #[arg(long)]
+    club: Option<String>,
 
-    /// [Optional] Maximum travel time in hours
-    #[arg(long, default_value_t = 1.5)]
-    max_hours: f64,
+    /// [Optional] FFE player id (from FicheJoueur.aspx?Id=...) to check Elo brackets against
+    #[arg(long)]
+    player: Option<String>,
+
+    /// [Optional] Leave out events whose Elo bracket the player is outside of (needs a player)
+    #[arg(long)]
+    eligible_only: bool,
+
+    /// [Optional] Fetch who is registered for each event found (count, average Elo, titled players)
+    #[arg(long, conflicts_with_all = ["member", "day_trips", "plan_from"])]
+    registered: bool,
+
+    /// [Optional] With --validate-cache, geocode the suspicious entries again
+    #[arg(long, requires = "validate_cache")]
+    regeocode: bool,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // Start with our custom logger
-    init_logging();
-    dotenvy::dotenv().ok();
+/// Splits an optional "@hours" suffix off a `--member` value.
+fn parse_member(spec: &str) -> Result<(&str, Option<f64>), String> {
+    match spec.rsplit_once('@') {
+        Some((origin, hours)) => {
+            let hours = hours
+                .parse()
+                .map_err(|_| format!("Invalid travel limit in '{}'", spec))?;
+            Ok((origin, Some(hours)))
+        }
+        None => Ok((spec, None)),
+    }
+}
 
-    // --- 1. Argument Parsing with Clap ---
-    // This is now robust, typed, and provides help messages!
-    let cli = Cli::parse();
+/// Loads the daily ORS quota usage, unless tracking is disabled in `[quota]`.
+fn load_quota(settings: &AppConfig) -> Result<Option<Arc<QuotaTracker>>, Box<dyn Error>> {
+    if !settings.quota.enabled {
+        return Ok(None);
+    }
+    let quota = QuotaTracker::load(&settings.paths.quota, settings.quota.clone())?;
+    quota.log_summary();
+    Ok(Some(Arc::new(quota)))
+}
 
-    // Intelligently determine the year based on the current date
-    let current_date = chrono::Local::now().date_naive();
-    let year = if cli.month < current_date.month() {
-        current_date.year() + 1
-    } else {
-        current_date.year()
+/// Compares freshly scraped events with the last run's and records them. In-scope events
+/// an earlier run saw cancelled or postponed get their detail page read, so that they can
+/// come back; other known statuses are carried over until `refine_kept_events`.
+fn sync_events(
+    events: &mut [Event],
+    settings: &AppConfig,
+    client: &FfeClient,
+    scope: &SearchScope,
+) -> Result<EventSnapshot, Box<dyn Error>> {
+    let mut snapshot = EventSnapshot::load(&settings.paths.events)?;
+    let known_off: Vec<&mut Event> = events
+        .iter_mut()
+        .filter(|e| {
+            scope.contains(&e.department)
+                && !e.status.is_off()
+                && snapshot
+                    .get(e.id)
+                    .is_some_and(|known| known.status.is_off())
+        })
+        .collect();
+    if !known_off.is_empty() {
+        log::info!(
+            "Fetching {} detail pages of events seen cancelled or postponed...",
+            known_off.len()
+        );
+        refine_from_details(known_off, client);
+    }
+    snapshot.update(events.iter_mut()).log_summary();
+    snapshot.save()?;
+    Ok(snapshot)
+}
+
+/// Checks the configured player's Elo against each in-scope event's bracket, fetching
+/// detail pages for brackets the title names without limits ("Open A"). Returns the
+/// player's profile, if one is configured.
+fn check_eligibility(
+    events: &mut [Event],
+    settings: &AppConfig,
+    client: &FfeClient,
+    scope: &SearchScope,
+) -> Result<Option<PlayerProfile>, Box<dyn Error>> {
+    let Some(player) = &settings.search.player else {
+        if settings.filters.eligible_only {
+            return Err("--eligible-only needs a player ([search] player or --player)".into());
+        }
+        return Ok(None);
     };
+    let profile = fetch_player_profile(client, player)?;
     log::info!(
-        "Searching for events in month {} of year {}",
-        cli.month,
-        year
+        "Player {}: standard {:?}, rapid {:?}, blitz {:?}",
+        profile.name.as_deref().unwrap_or(player),
+        profile.standard.map(|r| r.elo),
+        profile.rapid.map(|r| r.elo),
+        profile.blitz.map(|r| r.elo)
     );
+    refine_rating_constraints(events, client, |e| scope.contains(&e.department));
+    mark_eligibility(events, &profile);
+    Ok(Some(profile))
+}
+
+/// Reads the detail page of the events left after routing, for their status, time
+/// control and Elo limits, records the statuses found, and checks the player's
+/// eligibility again since the Elo that counts depends on the time control. Callers then
+/// drop what the scope excludes.
+fn refine_kept_events<'a>(
+    events: impl IntoIterator<Item = &'a mut Event>,
+    client: &FfeClient,
+    snapshot: &mut EventSnapshot,
+    profile: Option<&PlayerProfile>,
+) -> Result<(), Box<dyn Error>> {
+    let mut events: Vec<&mut Event> = events.into_iter().collect();
+    log::info!("Fetching the detail pages of {} events...", events.len());
+    refine_from_details(events.iter_mut().map(|e| &mut **e), client);
+    if let Some(profile) = profile {
+        for event in events.iter_mut() {
+            event.eligibility = eligibility_for(event, profile);
+        }
+    }
+    snapshot.update(events).log_summary();
+    snapshot.save()?;
+    Ok(())
+}
 
-    // --- 2. Dependency Initialization ---
-    let config = OrsConfig::from_env()?;
-    let provider: Box<dyn RoutingProvider> = match config {
+fn log_ors_status(base_url: &str, status: &OrsStatus) {
+    log::info!(
+        "Local ORS at {} is ready (engine {}, services: {})",
+        base_url,
+        status.engine_version.as_deref().unwrap_or("unknown"),
+        status.services.join(", ")
+    );
+    for profile in &status.profiles {
+        log::info!(
+            "  profile {}: OSM data from {}, graph built {}",
+            profile.name,
+            profile.osm_date.as_deref().unwrap_or("?"),
+            profile.graph_date.as_deref().unwrap_or("?")
+        );
+    }
+}
+
+/// Builds the provider for `config`, returning it with the host used to key its layers.
+fn build_provider(
+    config: OrsConfig,
+    profile: &str,
+    limiter: &Limiter,
+    retry: &RetryPolicy,
+    layered_rate_limit: bool,
+    quota: Option<&Arc<QuotaTracker>>,
+    coverage: Option<&Coverage>,
+) -> (Box<dyn RoutingProvider>, String) {
+    match config {
         OrsConfig::Hybrid {
             api_key,
             local_base_url,
         } => {
-            log::info!("Provider mode: HYBRID (Remote Geocoding, Local Routing)");
-            let limiter = Limiter::new();
-            Box::new(HybridOrsProvider::new(api_key, limiter, local_base_url))
+            let mut provider = HybridOrsProvider::new(api_key, limiter.clone(), local_base_url)
+                .with_profile(profile)
+                .with_retry(retry.clone());
+            match coverage {
+                Some(coverage) => {
+                    log::info!(
+                        "Provider mode: HYBRID (Remote Geocoding, Local Routing within coverage)"
+                    );
+                    provider = provider.with_coverage(coverage.clone());
+                }
+                None => log::info!("Provider mode: HYBRID (Remote Geocoding, Local Routing)"),
+            }
+            if layered_rate_limit {
+                provider = provider.without_rate_limit();
+            }
+            if let Some(quota) = quota {
+                provider = provider.with_quota(quota.clone());
+            }
+            (Box::new(provider), ORS_HOST.to_string())
         }
         OrsConfig::Local { base_url } => {
             log::info!("Provider mode: LOCAL");
-            Box::new(LocalOrsProvider::new(base_url))
+            let host = reqwest::Url::parse(&base_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| "local".to_string());
+            let provider = LocalOrsProvider::new(base_url)
+                .with_profile(profile)
+                .with_retry(retry.clone());
+            (Box::new(provider), host)
         }
         OrsConfig::Remote { api_key } => {
             log::info!("Provider mode: REMOTE");
-            let limiter = Limiter::new();
-            Box::new(RemoteOrsProvider::new(api_key, limiter))
+            let mut provider = RemoteOrsProvider::new(api_key, limiter.clone())
+                .with_profile(profile)
+                .with_retry(retry.clone());
+            if layered_rate_limit {
+                provider = provider.without_rate_limit();
+            }
+            if let Some(quota) = quota {
+                provider = provider.with_quota(quota.clone());
+            }
+            (Box::new(provider), ORS_HOST.to_string())
+        }
+    }
+}
+
+/// Applies the CLI flags over the config file and environment.
+fn apply_cli_overrides(settings: &mut AppConfig, cli: &Cli) {
+    if let Some(max_hours) = cli.max_hours {
+        settings.search.max_hours = max_hours;
+        settings.day_trip.max_one_way_hours = max_hours;
+    }
+    if let Some(profile) = &cli.profile {
+        settings.search.profile = profile.clone();
+    }
+    if let Some(cache) = &cli.cache {
+        settings.paths.cache = cache.clone();
+    }
+    if let Some(output) = &cli.output {
+        settings.paths.output = output.clone();
+    }
+    if let Some(player) = &cli.player {
+        settings.search.player = Some(player.clone());
+    }
+    if cli.festivals {
+        settings.search.group_festivals = true;
+    }
+    if let Some(latest_home) = cli.latest_home {
+        settings.day_trip.latest_arrival_home = latest_home;
+    }
+    let filters = &mut settings.filters;
+    if !cli.in_department.is_empty() {
+        filters.departments = cli.in_department.clone();
+    }
+    if !cli.region.is_empty() {
+        filters.regions = cli.region.clone();
+    }
+    if !cli.exclude_department.is_empty() {
+        filters.exclude_departments = cli.exclude_department.clone();
+    }
+    if cli.max_hops.is_some() {
+        filters.max_hops = cli.max_hops;
+    }
+    if cli.ignore_travel_time {
+        filters.ignore_travel_time = true;
+    }
+    if cli.eligible_only {
+        filters.eligible_only = true;
+    }
+    if cli.include_cancelled {
+        filters.include_cancelled = true;
+    }
+    if !cli.time_control.is_empty() {
+        filters.time_controls = cli.time_control.clone();
+    }
+}
+
+/// What every mode works with, built once from the settings.
+struct Services {
+    provider: Box<dyn RoutingProvider>,
+    metrics: Option<Arc<ProviderMetrics>>,
+    cache: Arc<Mutex<GeoCache>>,
+    coverage: Option<Coverage>,
+    lookup: DepartmentLookup,
+    client: FfeClient,
+}
+
+impl Services {
+    /// Saves the cache and logs the provider's counters at the end of a run.
+    fn finish(&self, settings: &AppConfig) -> Result<(), Box<dyn Error>> {
+        save_cache(&self.cache, settings)?;
+        if let Some(metrics) = &self.metrics {
+            metrics.log_summary();
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a local ORS is up, waiting for it with `--wait-ready`.
+fn check_local_ors(
+    base_url: &str,
+    profile: &str,
+    cli: &Cli,
+    has_fallback: bool,
+) -> Result<(), Box<dyn Error>> {
+    let local = LocalOrsProvider::new(base_url.to_string()).with_profile(profile);
+    let status = if cli.wait_ready {
+        let timeout = Duration::from_secs(cli.ready_timeout * 60);
+        local.wait_until_ready(timeout, READY_POLL_INTERVAL)
+    } else {
+        local.check_ready()
+    };
+    match status {
+        Ok(status) => log_ors_status(base_url, &status),
+        Err(e) if has_fallback => {
+            log::warn!("Local ORS unavailable, relying on the fallback: {}", e)
         }
+        Err(e @ RoutingError::NotReady { .. }) if !cli.wait_ready => {
+            return Err(format!("{}. Use --wait-ready to wait for it.", e).into());
+        }
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// Builds the provider stack, the cache, the department data and the FFE client.
+fn build_services(settings: &AppConfig, cli: &Cli) -> Result<Services, Box<dyn Error>> {
+    let config = OrsConfig::resolve(&settings.provider)?;
+    let profile = settings.search.profile.as_str();
+    let limiter = Limiter::from_config(&settings.rate_limit);
+    // Layers listed in the config replace the built-in retries and rate limiting.
+    let layers = &settings.provider.layers;
+    let retry = if layers.contains(&LayerKind::Retry) {
+        RetryPolicy::none()
+    } else {
+        settings.retry.clone()
     };
-    let department_lookup = DepartmentLookup::new("src/departments.csv")?;
-    let mut cache = GeoCache::load_from_file("geo_cache.json")?;
-    let http_client = HttpClient::new();
+    let layered_rate_limit = layers.contains(&LayerKind::RateLimit);
+    let fallback = OrsConfig::resolve_fallback(&settings.provider)?;
+    let uses_remote = |config: &OrsConfig| !matches!(config, OrsConfig::Local { .. });
+    let quota = if uses_remote(&config) || fallback.as_ref().is_some_and(uses_remote) {
+        load_quota(settings)?
+    } else {
+        None
+    };
+    let coverage = match &settings.provider.coverage {
+        Some(path) => Some(Coverage::load(path, &settings.provider.coverage_regions)?),
+        None => None,
+    };
+    let build = |config| {
+        build_provider(
+            config,
+            profile,
+            &limiter,
+            &retry,
+            layered_rate_limit,
+            quota.as_ref(),
+            coverage.as_ref(),
+        )
+    };
+    if let OrsConfig::Local { base_url }
+    | OrsConfig::Hybrid {
+        local_base_url: base_url,
+        ..
+    } = &config
+    {
+        check_local_ors(base_url, profile, cli, fallback.is_some())?;
+    }
+    let cache = Arc::new(Mutex::new(GeoCache::load_from_file(&settings.paths.cache)?));
+    let (provider, host) = build(config);
+    let mut fallback = fallback.map(|config| build(config).0);
+    let mut stack = ProviderStack::new(provider);
+    for layer in layers.iter().rev() {
+        stack = match layer {
+            LayerKind::Trace => stack.trace(host.as_str()),
+            LayerKind::Metrics => stack.metrics(),
+            LayerKind::Cache => stack.cache(cache.clone()),
+            LayerKind::Retry => stack.retry(settings.retry.clone()),
+            LayerKind::RateLimit => stack.rate_limit(limiter.clone(), host.as_str()),
+            LayerKind::CircuitBreaker => {
+                stack.circuit_breaker(host.as_str(), settings.circuit_breaker.clone())
+            }
+            LayerKind::Fallback => match fallback.take() {
+                Some(fallback) => stack.fallback(fallback),
+                None => return Err("The fallback layer needs [provider] fallback to be set".into()),
+            },
+        };
+    }
+    if fallback.is_some() {
+        log::warn!("[provider] fallback is set but \"fallback\" is not in layers; ignoring it");
+    }
+    // Every search needs the cache, so it goes on the outside when not placed explicitly.
+    if !layers.contains(&LayerKind::Cache) {
+        stack = stack.cache(cache.clone());
+    }
+    let metrics = stack.metrics_handle();
+    let provider = stack.build();
+    let mut lookup = match &settings.paths.departments {
+        Some(path) => DepartmentLookup::new(path)?,
+        None => DepartmentLookup::embedded(),
+    };
+    if let Some(boundaries) = &settings.paths.boundaries {
+        lookup = lookup.with_boundaries(boundaries)?;
+    }
+    let client = FfeClient::new()
+        .with_retry(settings.retry.clone())
+        .with_limiter(limiter);
+    Ok(Services {
+        provider,
+        metrics,
+        cache,
+        coverage,
+        lookup,
+        client,
+    })
+}
 
-    let origin_query = department_lookup
-        .build_geocode_query(&cli.city, &cli.department)
-        .ok_or_else(|| format!("Unknown department code: {}", cli.department))?;
-    log::info!("Origin location set to: {}", origin_query);
+/// `--validate-cache`: checks every cached geocode against the department outlines.
+fn run_cache_validation(
+    cli: &Cli,
+    settings: &AppConfig,
+    services: &Services,
+) -> Result<(), Box<dyn Error>> {
+    if !services.lookup.has_boundaries() {
+        return Err("--validate-cache needs [paths] boundaries (or FFE_BOUNDARIES_FILE)".into());
+    }
+    let provider = cli.regeocode.then_some(services.provider.as_ref());
+    let report = validate_geocodes(&services.cache, &services.lookup, provider);
+    write_json(&report, &settings.paths.output, "Cache validation report")?;
+    save_cache(&services.cache, settings)
+}
+
+/// `--event`: writes one event's detail page, registered players and last known state.
+fn run_event_report(
+    id: EventId,
+    settings: &AppConfig,
+    client: &FfeClient,
+) -> Result<(), Box<dyn Error>> {
+    let detail = fetch_event_detail(client, id)?;
+    let players = fetch_registered_players(client, id)?;
+    let snapshot = EventSnapshot::load(&settings.paths.events)?;
+    let report = serde_json::json!({
+        "id": id,
+        "event": snapshot.get(id),
+        "detail": detail,
+        "players": players,
+    });
+    write_json(&report, &settings.paths.output, "Event details")
+}
+
+/// Whether results go to a CSV file rather than JSON.
+fn csv_output(settings: &AppConfig) -> bool {
+    settings
+        .paths
+        .output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"))
+}
+
+/// `--standings`: writes a past event's final standings.
+fn run_standings(
+    id: EventId,
+    cli: &Cli,
+    settings: &AppConfig,
+    client: &FfeClient,
+) -> Result<(), Box<dyn Error>> {
+    let mut standings = fetch_standings(client, id)?;
+    if let Some(club) = &cli.club {
+        standings.rows.retain(|s| s.in_club(club));
+    }
+    let path = &settings.paths.output;
+    if csv_output(settings) {
+        write_standings_csv(path, &standings)?;
+        log::info!("✅ Standings written to {}", path.display());
+        Ok(())
+    } else {
+        write_json(&standings.rows, path, "Standings")
+    }
+}
+
+/// `--crosstable`: writes a past event's crosstable.
+fn run_crosstable(
+    id: EventId,
+    cli: &Cli,
+    settings: &AppConfig,
+    client: &FfeClient,
+) -> Result<(), Box<dyn Error>> {
+    let mut crosstable = fetch_crosstable(client, id)?;
+    if let Some(club) = &cli.club {
+        crosstable.rows.retain(|r| r.standing.in_club(club));
+    }
+    let path = &settings.paths.output;
+    if csv_output(settings) {
+        write_crosstable_csv(path, &crosstable)?;
+        log::info!("✅ Crosstable written to {}", path.display());
+        Ok(())
+    } else {
+        write_json(&crosstable.rows, path, "Crosstable")
+    }
+}
+
+/// The origin given on the command line, or the named (or default) one from the config,
+/// with the travel limit a named origin sets.
+fn cli_origin(cli: &Cli, settings: &AppConfig) -> Result<(Origin, Option<f64>), Box<dyn Error>> {
+    let origin = if let Some(city) = &cli.city {
+        let department = cli
+            .department
+            .clone()
+            .ok_or("--city requires --department")?;
+        Origin::City {
+            city: city.clone(),
+            department,
+        }
+    } else if let Some(coords) = &cli.coords {
+        coords.parse()?
+    } else if let Some(code) = &cli.postal_code {
+        format!("postal:{}", code).parse()?
+    } else if let Some(code) = &cli.insee {
+        format!("insee:{}", code).parse()?
+    } else {
+        let name = cli
+            .origin
+            .clone()
+            .or_else(|| settings.search.default_origin.clone())
+            .ok_or("No origin given: use --city/--department, --coords, --postal-code, --insee or --origin")?;
+        let named = settings
+            .origin(&name)
+            .ok_or_else(|| format!("Unknown origin '{}' in config file", name))?;
+        return Ok((named.to_origin()?, named.max_hours));
+    };
+    Ok((origin, None))
+}
+
+/// Everything a search from one origin shares.
+struct SearchContext<'a> {
+    cli: &'a Cli,
+    settings: &'a AppConfig,
+    services: &'a Services,
+    origin: ResolvedOrigin,
+    /// Travel limit set by the named origin, for group searches.
+    origin_max_hours: Option<f64>,
+    scope: SearchScope,
+}
+
+impl SearchContext<'_> {
+    fn resolve(&self, origin: &Origin) -> Result<ResolvedOrigin, Box<dyn Error>> {
+        Ok(resolve_origin(
+            origin,
+            &self.services.lookup,
+            self.services.client.http(),
+            &self.settings.retry,
+            &mut self.services.cache.lock().unwrap(),
+        )?)
+    }
+
+    fn max_hours(&self) -> f64 {
+        self.settings.search.max_hours
+    }
+
+    fn rules(&self) -> ReachabilityRules<'_> {
+        ReachabilityRules {
+            max_hours: self.max_hours(),
+            scope: &self.scope,
+            ferries: &self.settings.ferries,
+        }
+    }
+
+    /// Writes `items`, grouped into festivals when `[search] group_festivals` is set.
+    fn write_events<T: serde::Serialize>(
+        &self,
+        items: Vec<T>,
+        event: impl Fn(&T) -> &Event,
+        what: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let path = &self.settings.paths.output;
+        if self.settings.search.group_festivals {
+            write_json(&group_festivals(items, event), path, "Festivals")
+        } else {
+            write_json(&items, path, what)
+        }
+    }
+
+    /// `--coverage-report`: where each of `events` lies relative to the local graph.
+    fn write_coverage_report(&self, events: &[Event]) -> Result<(), Box<dyn Error>> {
+        let (Some(path), Some(coverage)) = (&self.cli.coverage_report, &self.services.coverage)
+        else {
+            return Ok(());
+        };
+        let report = event_coverage(
+            self.origin.query.as_str(),
+            events,
+            &self.services.lookup,
+            &self.services.cache.lock().unwrap(),
+            coverage,
+        );
+        write_json(&report, path, "Coverage report")
+    }
+}
+
+/// `--plan-from`/`--plan-to`: plans a tour of reachable events.
+fn run_tour(ctx: &SearchContext, start: NaiveDate, end: NaiveDate) -> Result<(), Box<dyn Error>> {
+    let (settings, services) = (ctx.settings, ctx.services);
+    log::info!("Planning a tour between {} and {}", start, end);
+    let mut all_events = get_events_for_range(start, end, &services.client, &services.lookup)?;
+    let mut snapshot = sync_events(&mut all_events, settings, &services.client, &ctx.scope)?;
+    let profile = check_eligibility(&mut all_events, settings, &services.client, &ctx.scope)?;
+    let all_events = road_connected_events(ctx.origin.department.as_deref(), &all_events);
+    // Routes from home are cached, so the planner routes these again for free.
+    let mut reachable = filter_reachable_events(
+        &ctx.origin,
+        &all_events,
+        &services.lookup,
+        services.provider.as_ref(),
+        &ctx.rules(),
+    );
+    refine_kept_events(
+        reachable.reachable.iter_mut().map(|r| &mut r.event),
+        &services.client,
+        &mut snapshot,
+        profile.as_ref(),
+    )?;
+    reachable.recheck(&ctx.scope);
+    let events: Vec<Event> = reachable.reachable.into_iter().map(|r| r.event).collect();
+    let constraints = TourConstraints {
+        start,
+        end,
+        max_events: ctx.cli.max_events,
+        max_hours_per_trip: ctx.max_hours(),
+        min_rest_days: ctx.cli.min_rest_days,
+        chain_max_gap_days: ctx.cli.chain_gap_days,
+        objective: ctx.cli.optimise,
+    };
+    let plan = plan_tour(
+        &ctx.origin.query,
+        &events,
+        &services.lookup,
+        services.provider.as_ref(),
+        &constraints,
+    );
+    write_json(&plan, &settings.paths.output, "Tour plan")?;
+    ctx.write_coverage_report(&all_events)?;
+    services.finish(settings)
+}
 
-    // --- 3. Execute SDK Logic ---
-    let all_events = get_events_for_month(cli.month, year, &http_client, &department_lookup)?;
+/// `--month`: searches one month's events, in whichever way the flags ask for.
+fn run_month_search(ctx: &SearchContext) -> Result<(), Box<dyn Error>> {
+    let (settings, services) = (ctx.settings, ctx.services);
+    let month = ctx.cli.month.ok_or("--month is required")?;
+    // Intelligently determine the year based on the current date
+    let current_date = chrono::Local::now().date_naive();
+    let year = if month < current_date.month() {
+        current_date.year() + 1
+    } else {
+        current_date.year()
+    };
+    log::info!("Searching for events in month {} of year {}", month, year);
+
+    let mut all_events = get_events_for_month(month, year, &services.client, &services.lookup)?;
     log::info!(
         "Found {} total events in France for {}/{}",
         all_events.len(),
-        cli.month,
+        month,
         year
     );
+    let mut snapshot = sync_events(&mut all_events, settings, &services.client, &ctx.scope)?;
+    let profile = check_eligibility(&mut all_events, settings, &services.client, &ctx.scope)?;
+    let profile = profile.as_ref();
 
-    let reachable_events = filter_reachable_events(
-        &cli.city,
-        &origin_query,
-        &all_events,
-        &department_lookup,
-        provider.as_ref(),
-        &mut cache,
-        cli.max_hours,
+    if settings.filters.ignore_travel_time {
+        list_events_in_scope(ctx, &all_events, &mut snapshot, profile)?;
+    } else if ctx.cli.day_trips {
+        search_day_trips(ctx, &all_events, &mut snapshot, profile)?;
+    } else if !ctx.cli.member.is_empty() {
+        search_group(ctx, &all_events, &mut snapshot, profile)?;
+    } else {
+        search_reachable(ctx, &all_events, &mut snapshot, profile)?;
+    }
+
+    ctx.write_coverage_report(&all_events)?;
+    services.finish(settings)
+}
+
+/// `--ignore-travel-time`: every event in scope, without routing.
+fn list_events_in_scope(
+    ctx: &SearchContext,
+    all_events: &[Event],
+    snapshot: &mut EventSnapshot,
+    profile: Option<&PlayerProfile>,
+) -> Result<(), Box<dyn Error>> {
+    let client = &ctx.services.client;
+    let mut events = ctx.scope.filter(all_events);
+    refine_kept_events(&mut events, client, snapshot, profile)?;
+    events.retain(|e| ctx.scope.exclusion(e).is_none());
+    log::info!("Found {} events in the search scope.", events.len());
+    if ctx.cli.registered {
+        attach_field_stats(&mut events, client);
+    }
+    ctx.write_events(events, |e| e, "Events in scope")
+}
+
+/// `--day-trips`: classifies events as day trip, overnight stay or infeasible.
+fn search_day_trips(
+    ctx: &SearchContext,
+    all_events: &[Event],
+    snapshot: &mut EventSnapshot,
+    profile: Option<&PlayerProfile>,
+) -> Result<(), Box<dyn Error>> {
+    let services = ctx.services;
+    let events = road_connected_events(ctx.origin.department.as_deref(), all_events);
+    let mut assessments = assess_day_trips(
+        &ctx.origin.query,
+        &ctx.scope.filter(&events),
+        &services.client,
+        &services.lookup,
+        services.provider.as_ref(),
+        &ctx.settings.day_trip,
+    );
+    // Infeasible events were never worth a detail page; leave them as they are.
+    refine_kept_events(
+        assessments
+            .iter_mut()
+            .filter(|a| a.feasibility != TripFeasibility::Infeasible)
+            .map(|a| &mut a.event),
+        &services.client,
+        snapshot,
+        profile,
+    )?;
+    assessments.retain(|a| ctx.scope.exclusion(&a.event).is_none());
+    ctx.write_events(assessments, |a| &a.event, "Day trip assessments")
+}
+
+/// `--member`: events every traveller can reach, with a meeting point to share the car.
+fn search_group(
+    ctx: &SearchContext,
+    all_events: &[Event],
+    snapshot: &mut EventSnapshot,
+    profile: Option<&PlayerProfile>,
+) -> Result<(), Box<dyn Error>> {
+    let (cli, settings, services) = (ctx.cli, ctx.settings, ctx.services);
+    let mut members = vec![GroupMember::from_origin(&ctx.origin, ctx.origin_max_hours)];
+    for spec in &cli.member {
+        let (name, limit) = parse_member(spec)?;
+        let limit = limit.or_else(|| settings.origin(name).and_then(|o| o.max_hours));
+        let member = ctx.resolve(&settings.origin_from_spec(name)?)?;
+        members.push(GroupMember::from_origin(&member, limit));
+    }
+    let mut meeting_points = Vec::new();
+    for spec in &cli.meeting_point {
+        let town = ctx.resolve(&settings.origin_from_spec(spec)?)?;
+        meeting_points.push(town.query);
+    }
+
+    let events = road_connected_events(ctx.origin.department.as_deref(), all_events);
+    let mut group_events = filter_group_reachable_events(
+        &members,
+        &ctx.scope.filter(&events),
+        &services.lookup,
+        services.provider.as_ref(),
+        ctx.max_hours(),
+        &meeting_points,
+    );
+    refine_kept_events(
+        group_events.iter_mut().map(|g| &mut g.event),
+        &services.client,
+        snapshot,
+        profile,
+    )?;
+    group_events.retain(|g| ctx.scope.exclusion(&g.event).is_none());
+    log::info!(
+        "Found {} events reachable by all {} travellers.",
+        group_events.len(),
+        members.len()
+    );
+    ctx.write_events(group_events, |g| &g.event, "Group events")
+}
+
+/// The default search: events reachable from the origin within the travel limit.
+fn search_reachable(
+    ctx: &SearchContext,
+    all_events: &[Event],
+    snapshot: &mut EventSnapshot,
+    profile: Option<&PlayerProfile>,
+) -> Result<(), Box<dyn Error>> {
+    let services = ctx.services;
+    let mut results = filter_reachable_events(
+        &ctx.origin,
+        all_events,
+        &services.lookup,
+        services.provider.as_ref(),
+        &ctx.rules(),
     );
+    refine_kept_events(
+        results.reachable.iter_mut().map(|r| &mut r.event),
+        &services.client,
+        snapshot,
+        profile,
+    )?;
+    results.recheck(&ctx.scope);
 
-    // --- 4. Output Results ---
     log::info!(
         "Found {} events reachable from {} within {} hours.",
-        reachable_events.len(),
-        cli.city,
-        cli.max_hours
+        results.reachable.len(),
+        ctx.origin.label,
+        ctx.max_hours()
     );
+    results.log_exclusions();
+    if ctx.cli.registered {
+        attach_field_stats(
+            results.reachable.iter_mut().map(|r| &mut r.event),
+            &services.client,
+        );
+    }
+    let path = &ctx.settings.paths.output;
+    if ctx.settings.search.group_festivals {
+        write_json(&FestivalResults::from(results), path, "Festivals")
+    } else {
+        write_json(&results, path, "Reachable events")
+    }
+}
 
-    let json_output = serde_json::to_string_pretty(&reachable_events)?;
-    let mut file = File::create("reachable_events.json")?;
-    file.write_all(json_output.as_bytes())?;
-    log::info!("✅ Reachable events written to reachable_events.json");
+fn main() -> Result<(), Box<dyn Error>> {
+    // Start with our custom logger
+    init_logging();
+    dotenvy::dotenv().ok();
 
-    cache.save_to_file("geo_cache.json")?;
-    log::info!("💾 Cache saved to geo_cache.json");
+    // --- 1. Argument Parsing with Clap ---
+    let cli = Cli::parse();
 
+    // --- 2. Configuration: defaults < config file < env vars < CLI flags ---
+    let mut settings = AppConfig::load(cli.config.as_deref())?;
+    apply_cli_overrides(&mut settings, &cli);
+
+    // --- 3. Dependency Initialization ---
+    let services = build_services(&settings, &cli)?;
+    if cli.coverage_report.is_some() && services.coverage.is_none() {
+        return Err("--coverage-report needs [provider] coverage".into());
+    }
+
+    // --- 4. Execute SDK Logic ---
+    if cli.validate_cache {
+        return run_cache_validation(&cli, &settings, &services);
+    }
+    if let Some(id) = cli.event {
+        return run_event_report(id, &settings, &services.client);
+    }
+    if let Some(id) = cli.standings {
+        return run_standings(id, &cli, &settings, &services.client);
+    }
+    if let Some(id) = cli.crosstable {
+        return run_crosstable(id, &cli, &settings, &services.client);
+    }
+
+    let (origin, origin_max_hours) = cli_origin(&cli, &settings)?;
+    let origin = resolve_origin(
+        &origin,
+        &services.lookup,
+        services.client.http(),
+        &settings.retry,
+        &mut services.cache.lock().unwrap(),
+    )?;
+    log::info!("Origin location set to: {}", origin.query);
+    let scope = settings
+        .filters
+        .scope(&services.lookup, origin.department.as_deref())?;
+    let ctx = SearchContext {
+        cli: &cli,
+        settings: &settings,
+        services: &services,
+        origin,
+        origin_max_hours,
+        scope,
+    };
+    match (cli.plan_from, cli.plan_to) {
+        (Some(start), Some(end)) => run_tour(&ctx, start, end),
+        _ => run_month_search(&ctx),
+    }
+}
+
+fn save_cache(cache: &Mutex<GeoCache>, settings: &AppConfig) -> Result<(), Box<dyn Error>> {
+    cache.lock().unwrap().save_to_file(&settings.paths.cache)?;
+    log::info!("💾 Cache saved to {}", settings.paths.cache.display());
+    Ok(())
+}
+
+/// Writes `value` as pretty JSON, logging what was written where.
+fn write_json<T: serde::Serialize>(
+    value: &T,
+    path: &Path,
+    what: &str,
+) -> Result<(), Box<dyn Error>> {
+    let json_output = serde_json::to_string_pretty(value)?;
+    let mut file = File::create(path)?;
+    file.write_all(json_output.as_bytes())?;
+    log::info!("✅ {} written to {}", what, path.display());
     Ok(())
 }
@@ -0,0 +1,199 @@
+use super::departments::DepartmentLookup;
+use super::events::Event;
+use super::routing::{
+    geocode::GeocodeQuery,
+    origin::ResolvedOrigin,
+    route::{get_road_distance, log_routing_failure},
+    service::RoutingProvider,
+};
+use serde::Serialize;
+
+/// One traveller of a carpooling group.
+#[derive(Debug, Clone)]
+pub struct GroupMember {
+    /// Display name, also used for same-town matching.
+    pub name: String,
+    /// Geocode query of the member's starting point.
+    pub query: GeocodeQuery,
+    /// Per-member limit; the group limit applies when `None`.
+    pub max_hours: Option<f64>,
+}
+
+impl GroupMember {
+    pub fn from_origin(origin: &ResolvedOrigin, max_hours: Option<f64>) -> Self {
+        Self {
+            name: origin.label.clone(),
+            query: origin.query.clone(),
+            max_hours,
+        }
+    }
+}
+
+/// Travel of one member to a destination.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemberLeg {
+    pub member: String,
+    pub distance_km: f64,
+    pub duration_hours: f64,
+}
+
+/// A town where the group gathers before sharing one car to the event.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingPoint {
+    pub town: String,
+    /// Legs from each member to the meeting town.
+    pub legs: Vec<MemberLeg>,
+    /// Shared drive from the meeting town to the event.
+    pub shared_hours: f64,
+    /// Sum of all member legs plus the shared drive.
+    pub total_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupReachableEvent {
+    pub event: Event,
+    /// Each member's direct drive to the event.
+    pub legs: Vec<MemberLeg>,
+    /// Total driving when everyone goes separately.
+    pub total_hours: f64,
+    /// Best candidate meeting town, if any candidate could be routed.
+    pub meeting_point: Option<MeetingPoint>,
+}
+
+/// Keeps the events that every member can reach within their limit, and picks the
+/// meeting town from `meeting_candidates` that minimises total driving.
+///
+/// Meeting candidates are geocode queries (e.g., "Vitré, Ille-et-Vilaine").
+pub fn filter_group_reachable_events(
+    members: &[GroupMember],
+    events: &[Event],
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+    max_hours: f64,
+    meeting_candidates: &[GeocodeQuery],
+) -> Vec<GroupReachableEvent> {
+    let mut reachable = Vec::new();
+    log::info!(
+        "Filtering {} events for a group of {} (max {:.2} hours each)...",
+        events.len(),
+        members.len(),
+        max_hours
+    );
+
+    'events: for event in events {
+        let Some(destination) = lookup.build_geocode_query(&event.location, &event.department)
+        else {
+            continue;
+        };
+
+        let mut legs = Vec::with_capacity(members.len());
+        for member in members {
+            let limit = member.max_hours.unwrap_or(max_hours);
+            let leg = if member
+                .name
+                .trim()
+                .eq_ignore_ascii_case(event.location.trim())
+            {
+                MemberLeg {
+                    member: member.name.clone(),
+                    distance_km: 0.0,
+                    duration_hours: 0.0,
+                }
+            } else {
+                match get_road_distance(&member.query, &destination, provider) {
+                    Ok(summary) => MemberLeg {
+                        member: member.name.clone(),
+                        distance_km: summary.distance_km,
+                        duration_hours: summary.duration_hours,
+                    },
+                    Err(e) => {
+                        log_routing_failure(&member.query, &destination, &e);
+                        if e.is_quota_exhausted() {
+                            break 'events;
+                        }
+                        continue 'events;
+                    }
+                }
+            };
+            if leg.duration_hours > limit {
+                log::trace!(
+                    "[TOO FAR] {} for {} ({:.2} hrs > {:.2})",
+                    event.title,
+                    member.name,
+                    leg.duration_hours,
+                    limit
+                );
+                continue 'events;
+            }
+            legs.push(leg);
+        }
+
+        let total_hours = legs.iter().map(|l| l.duration_hours).sum();
+        let meeting_point = best_meeting_point(members, &destination, meeting_candidates, provider);
+        log::info!(
+            "[REACHABLE - GROUP] {} at {} ({:.2} hrs in total)",
+            event.title,
+            event.location,
+            total_hours
+        );
+        reachable.push(GroupReachableEvent {
+            event: event.clone(),
+            legs,
+            total_hours,
+            meeting_point,
+        });
+    }
+    reachable
+}
+
+/// Returns the candidate town minimising member legs plus the shared drive to `destination`.
+fn best_meeting_point(
+    members: &[GroupMember],
+    destination: &GeocodeQuery,
+    candidates: &[GeocodeQuery],
+    provider: &dyn RoutingProvider,
+) -> Option<MeetingPoint> {
+    let mut best: Option<MeetingPoint> = None;
+
+    'candidates: for town in candidates {
+        let shared_hours = match get_road_distance(town, destination, provider) {
+            Ok(summary) => summary.duration_hours,
+            Err(e) => {
+                log_routing_failure(town, destination, &e);
+                if e.is_quota_exhausted() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let mut legs = Vec::with_capacity(members.len());
+        for member in members {
+            match get_road_distance(&member.query, town, provider) {
+                Ok(summary) => legs.push(MemberLeg {
+                    member: member.name.clone(),
+                    distance_km: summary.distance_km,
+                    duration_hours: summary.duration_hours,
+                }),
+                Err(e) => {
+                    log_routing_failure(&member.query, town, &e);
+                    if e.is_quota_exhausted() {
+                        break 'candidates;
+                    }
+                    continue 'candidates;
+                }
+            }
+        }
+
+        let total_hours = shared_hours + legs.iter().map(|l| l.duration_hours).sum::<f64>();
+        if best.as_ref().is_none_or(|b| total_hours < b.total_hours) {
+            best = Some(MeetingPoint {
+                town: town.text.clone(),
+                legs,
+                shared_hours,
+                total_hours,
+            });
+        }
+    }
+    best
+}
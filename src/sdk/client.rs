@@ -0,0 +1,91 @@
+use reqwest::{
+    blocking::Client,
+    header::{HeaderMap, USER_AGENT},
+};
+
+use super::error::ScrapeError;
+use super::util::rate_limit::{LimitKey, Limiter};
+use super::util::retry::{RateLimitInfo, RetryPolicy};
+
+/// HTTP client for the FFE website, retrying transient failures.
+pub struct FfeClient {
+    http: Client,
+    headers: HeaderMap,
+    retry: RetryPolicy,
+    limiter: Option<Limiter>,
+}
+
+impl Default for FfeClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FfeClient {
+    pub fn new() -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, "Mozilla/5.0".parse().unwrap());
+        Self {
+            http: Client::new(),
+            headers,
+            retry: RetryPolicy::default(),
+            limiter: None,
+        }
+    }
+
+    /// Sets the retry policy for failed page loads (default: 3 attempts).
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Throttles page loads, keyed by host (default: unthrottled).
+    pub fn with_limiter(mut self, limiter: Limiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// The underlying HTTP client, for calls to other services.
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
+    /// GETs an FFE page, turning transport failures and error statuses into `ScrapeError`.
+    pub(crate) fn fetch_page(&self, url: &str) -> Result<String, ScrapeError> {
+        self.retry.run(
+            url,
+            ScrapeError::is_retryable,
+            ScrapeError::retry_after,
+            || self.fetch_once(url),
+        )
+    }
+
+    fn fetch_once(&self, url: &str) -> Result<String, ScrapeError> {
+        let request_error = |source| ScrapeError::Request {
+            url: url.to_string(),
+            source,
+        };
+        if let Some(limiter) = &self.limiter {
+            let host = reqwest::Url::parse(url)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_string))
+                .unwrap_or_default();
+            limiter.wait(&LimitKey::host(host));
+        }
+        let response = self
+            .http
+            .get(url)
+            .headers(self.headers.clone())
+            .send()
+            .map_err(request_error)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ScrapeError::Status {
+                url: url.to_string(),
+                status,
+                retry_after: RateLimitInfo::from_headers(response.headers()).retry_after,
+            });
+        }
+        response.text().map_err(request_error)
+    }
+}
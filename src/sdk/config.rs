@@ -1,4 +1,23 @@
-use std::env;
+use crate::sdk::daytrip::DayTripPolicy;
+use crate::sdk::error::ConfigError;
+use crate::sdk::filters::SearchFilters;
+use crate::sdk::routing::layer::{CircuitBreakerConfig, LayerKind};
+use crate::sdk::routing::origin::Origin;
+use crate::sdk::territory::FerryConfig;
+use crate::sdk::util::quota::QuotaConfig;
+pub use crate::sdk::util::rate_limit::RateLimitConfig;
+use crate::sdk::util::retry::RetryPolicy;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the directory looked up inside each XDG config dir.
+const APP_DIR: &str = "ffe-rust";
+/// Name of the config file inside `APP_DIR`.
+const CONFIG_FILE: &str = "config.toml";
 
 pub enum OrsConfig {
     Remote {
@@ -14,39 +33,365 @@ pub enum OrsConfig {
 }
 
 impl OrsConfig {
-    /// Creates configuration from environment variables.
-    /// Priority:
-    /// 1. If ORS_LOCAL_URL is set, use local provider.
-    /// 2. Else, if ORS_API_KEY is set, use remote provider.
-    /// 3. Else, return an error.
-    pub fn from_env() -> Result<Self, String> {
-        let local_url = env::var("ORS_LOCAL_URL");
-        let api_key = env::var("ORS_API_KEY");
-
-        match (local_url, api_key) {
-            // 1. If BOTH are set, use Hybrid mode.
-            (Ok(local_base_url), Ok(api_key)) => {
+    /// Creates configuration from environment variables alone, as `resolve` does
+    /// without a config file:
+    /// 1. ORS_PROVIDER picks the mode, if set.
+    /// 2. Else, both ORS_LOCAL_URL and ORS_API_KEY set means hybrid.
+    /// 3. Else, ORS_LOCAL_URL means local and ORS_API_KEY means remote.
+    /// 4. Else, return an error.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::resolve(&ProviderConfig::default())
+    }
+
+    /// Creates configuration from the `[provider]` section of the config file,
+    /// with `ORS_PROVIDER`, `ORS_LOCAL_URL` and `ORS_API_KEY` overriding it.
+    ///
+    /// When no mode is given explicitly, it is inferred from which of the
+    /// local URL and API key are available (both means hybrid).
+    pub fn resolve(provider: &ProviderConfig) -> Result<Self, ConfigError> {
+        let local_url = env::var("ORS_LOCAL_URL")
+            .ok()
+            .or_else(|| provider.local_url.clone());
+        let api_key = env::var("ORS_API_KEY")
+            .ok()
+            .or_else(|| provider.api_key.clone());
+        let mode = match env::var("ORS_PROVIDER") {
+            Ok(value) => Some(value.parse::<ProviderMode>()?),
+            Err(_) => provider.mode,
+        };
+        Self::for_mode(mode, local_url, api_key)
+    }
+
+    /// Configuration for the `[provider] fallback` mode, if one is set.
+    pub fn resolve_fallback(provider: &ProviderConfig) -> Result<Option<Self>, ConfigError> {
+        let Some(mode) = provider.fallback else {
+            return Ok(None);
+        };
+        let local_url = env::var("ORS_LOCAL_URL")
+            .ok()
+            .or_else(|| provider.local_url.clone());
+        let api_key = env::var("ORS_API_KEY")
+            .ok()
+            .or_else(|| provider.api_key.clone());
+        Self::for_mode(Some(mode), local_url, api_key).map(Some)
+    }
+
+    fn for_mode(
+        mode: Option<ProviderMode>,
+        local_url: Option<String>,
+        api_key: Option<String>,
+    ) -> Result<Self, ConfigError> {
+        match (mode, local_url, api_key) {
+            (Some(ProviderMode::Hybrid), Some(local_base_url), Some(api_key))
+            | (None, Some(local_base_url), Some(api_key)) => {
+                // 1. If BOTH are set, use Hybrid mode.
                 log::info!("Using Hybrid mode: LOCAL for routing, REMOTE for geocoding.");
                 Ok(OrsConfig::Hybrid {
                     api_key,
                     local_base_url,
                 })
             }
-            // 2. If only local is set, use Local mode.
-            (Ok(base_url), Err(_)) => {
+            (Some(ProviderMode::Local), Some(base_url), _) | (None, Some(base_url), None) => {
+                // 2. If only local is set, use Local mode.
                 log::info!("Using local-only OpenRouteService instance at {}", base_url);
                 Ok(OrsConfig::Local { base_url })
             }
-            // 3. If only remote is set, use Remote mode.
-            (Err(_), Ok(api_key)) => {
+            (Some(ProviderMode::Remote), _, Some(api_key)) | (None, None, Some(api_key)) => {
+                // 3. If only remote is set, use Remote mode.
                 log::info!("Using remote-only OpenRouteService API");
                 Ok(OrsConfig::Remote { api_key })
             }
+            (Some(mode), _, _) => Err(ConfigError::IncompleteProvider(mode.to_string())),
             // 4. If neither is set, error out.
-            (Err(_), Err(_)) => Err(
-                "Missing ORS configuration: Please set ORS_LOCAL_URL and/or ORS_API_KEY"
+            (None, None, None) => Err(ConfigError::MissingProvider),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderMode {
+    Remote,
+    Local,
+    Hybrid,
+}
+
+impl std::str::FromStr for ProviderMode {
+    type Err = ConfigError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "remote" => Ok(ProviderMode::Remote),
+            "local" => Ok(ProviderMode::Local),
+            "hybrid" => Ok(ProviderMode::Hybrid),
+            other => Err(ConfigError::InvalidValue {
+                name: "provider mode".to_string(),
+                value: other.to_string(),
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ProviderMode::Remote => "remote",
+            ProviderMode::Local => "local",
+            ProviderMode::Hybrid => "hybrid",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A named starting point, e.g. `home = { city = "Rennes", department = "35" }`.
+///
+/// Exactly one of `city` (with `department`), `postal_code`, `insee` or `coords`
+/// (`"lat,lon"`) must be set.
+///
+/// `max_hours` optionally caps this origin's travel in multi-origin searches.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NamedOrigin {
+    pub city: Option<String>,
+    pub department: Option<String>,
+    pub postal_code: Option<String>,
+    pub insee: Option<String>,
+    pub coords: Option<String>,
+    /// Per-origin travel limit used in group searches.
+    pub max_hours: Option<f64>,
+}
+
+impl NamedOrigin {
+    /// Converts the table entry into an `Origin`.
+    pub fn to_origin(&self) -> Result<Origin, ConfigError> {
+        match (
+            &self.city,
+            &self.department,
+            &self.postal_code,
+            &self.insee,
+            &self.coords,
+        ) {
+            (Some(city), Some(department), None, None, None) => Ok(Origin::City {
+                city: city.clone(),
+                department: department.clone(),
+            }),
+            (None, None, Some(code), None, None) => format!("postal:{}", code).parse(),
+            (None, None, None, Some(code), None) => format!("insee:{}", code).parse(),
+            (None, None, None, None, Some(coords)) => coords.parse(),
+            _ => Err(ConfigError::InvalidOrigin(
+                "a named origin needs exactly one of city+department, postal_code, insee or coords"
                     .to_string(),
-            ),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Origin used when neither `--city` nor `--origin` is given.
+    pub default_origin: Option<String>,
+    pub max_hours: f64,
+    /// ORS routing profile, e.g. "driving-car".
+    pub profile: String,
+    /// Output festivals (tournaments held together) instead of individual events.
+    pub group_festivals: bool,
+    /// FFE player id (the `Id` of `FicheJoueur.aspx`) whose Elo is checked against the
+    /// events' brackets.
+    pub player: Option<String>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            default_origin: None,
+            max_hours: 1.5,
+            profile: "driving-car".to_string(),
+            group_festivals: false,
+            player: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProviderConfig {
+    /// Forces a provider; inferred from `local_url`/`api_key` when unset.
+    pub mode: Option<ProviderMode>,
+    pub api_key: Option<String>,
+    pub local_url: Option<String>,
+    /// Provider used by the `fallback` layer when the main one is unavailable.
+    pub fallback: Option<ProviderMode>,
+    /// Layers wrapped around the provider, outermost first,
+    /// e.g. `["trace", "metrics", "cache", "retry", "rate_limit"]`.
+    pub layers: Vec<LayerKind>,
+    /// `.poly` file, or directory of them, the local graph was built from. In hybrid
+    /// mode, routes leaving this area go to the remote API instead.
+    pub coverage: Option<PathBuf>,
+    /// Extracts `coverage` must include, by file stem; a missing one fails the run.
+    pub coverage_regions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PathsConfig {
+    /// Replaces the department table compiled into the binary.
+    pub departments: Option<PathBuf>,
+    /// Geocodes and routes of the `cache` provider layer.
+    pub cache: PathBuf,
+    pub output: PathBuf,
+    /// Daily ORS quota usage, kept between runs.
+    pub quota: PathBuf,
+    /// GeoJSON outlines of the departments, used to validate cached geocodes.
+    pub boundaries: Option<PathBuf>,
+    /// Events seen by earlier runs, to report cancellations and postponements.
+    pub events: PathBuf,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self {
+            departments: None,
+            cache: PathBuf::from("geo_cache.json"),
+            output: PathBuf::from("reachable_events.json"),
+            quota: PathBuf::from("ors_quota.json"),
+            boundaries: None,
+            events: PathBuf::from("ffe_events.json"),
         }
     }
 }
+
+/// Settings loaded from `config.toml`.
+///
+/// Precedence, from lowest to highest: built-in defaults, the config file,
+/// environment variables, then CLI flags (applied by the caller).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub origins: HashMap<String, NamedOrigin>,
+    pub search: SearchConfig,
+    pub filters: SearchFilters,
+    pub provider: ProviderConfig,
+    pub rate_limit: RateLimitConfig,
+    pub paths: PathsConfig,
+    pub day_trip: DayTripPolicy,
+    pub retry: RetryPolicy,
+    pub quota: QuotaConfig,
+    pub circuit_breaker: CircuitBreakerConfig,
+    pub ferries: FerryConfig,
+}
+
+impl AppConfig {
+    /// Loads the config file and applies environment overrides.
+    ///
+    /// An explicit path (from `--config` or `FFE_CONFIG`) must exist. Otherwise the
+    /// XDG config dirs are searched, and built-in defaults are used if none has a file.
+    pub fn load(explicit_path: Option<&Path>) -> Result<Self, ConfigError> {
+        let explicit = explicit_path
+            .map(Path::to_path_buf)
+            .or_else(|| env::var_os("FFE_CONFIG").map(PathBuf::from));
+
+        let mut config = match explicit {
+            Some(path) => Self::from_file(&path)?,
+            None => match Self::discover() {
+                Some(path) => Self::from_file(&path)?,
+                None => {
+                    log::debug!("No config file found, using defaults");
+                    Self::default()
+                }
+            },
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    /// Parses a TOML config file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let config = toml::from_str(&data).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        log::info!("Loaded configuration from {}", path.display());
+        Ok(config)
+    }
+
+    /// Returns the first `ffe-rust/config.toml` found in `$XDG_CONFIG_HOME`
+    /// (default `~/.config`), then in each of `$XDG_CONFIG_DIRS` (default `/etc/xdg`).
+    pub fn discover() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        let config_dirs = env::var("XDG_CONFIG_DIRS")
+            .ok()
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| "/etc/xdg".to_string());
+
+        config_home
+            .into_iter()
+            .chain(config_dirs.split(':').map(PathBuf::from))
+            .map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Applies environment variable overrides on top of the file values.
+    /// Provider credentials are handled by `OrsConfig::resolve`.
+    fn apply_env(&mut self) -> Result<(), ConfigError> {
+        if let Ok(value) = env::var("FFE_MAX_HOURS") {
+            self.search.max_hours = parse_env("FFE_MAX_HOURS", value)?;
+        }
+        if let Ok(value) = env::var("ORS_PROFILE") {
+            self.search.profile = value;
+        }
+        if let Ok(value) = env::var("FFE_PLAYER_ID") {
+            self.search.player = Some(value);
+        }
+        if let Ok(value) = env::var("ORS_RATE_LIMIT_PER_MINUTE") {
+            self.rate_limit.per_minute = parse_env("ORS_RATE_LIMIT_PER_MINUTE", value)?;
+        }
+        if let Some(value) = env::var_os("FFE_DEPARTMENTS_FILE") {
+            self.paths.departments = Some(PathBuf::from(value));
+        }
+        if let Some(value) = env::var_os("FFE_CACHE_FILE") {
+            self.paths.cache = PathBuf::from(value);
+        }
+        if let Some(value) = env::var_os("FFE_OUTPUT_FILE") {
+            self.paths.output = PathBuf::from(value);
+        }
+        if let Some(value) = env::var_os("FFE_QUOTA_FILE") {
+            self.paths.quota = PathBuf::from(value);
+        }
+        if let Some(value) = env::var_os("FFE_BOUNDARIES_FILE") {
+            self.paths.boundaries = Some(PathBuf::from(value));
+        }
+        if let Some(value) = env::var_os("FFE_EVENTS_FILE") {
+            self.paths.events = PathBuf::from(value);
+        }
+        Ok(())
+    }
+
+    /// Looks up a named origin from the `[origins]` table.
+    pub fn origin(&self, name: &str) -> Option<&NamedOrigin> {
+        self.origins.get(name)
+    }
+
+    /// Parses an origin given on the command line: a named origin from the config
+    /// file, or anything `Origin::from_str` accepts.
+    pub fn origin_from_spec(&self, spec: &str) -> Result<Origin, ConfigError> {
+        match self.origin(spec) {
+            Some(named) => named.to_origin(),
+            None => spec.parse(),
+        }
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(name: &str, value: String) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| ConfigError::InvalidValue {
+        name: name.to_string(),
+        value,
+    })
+}
@@ -0,0 +1,249 @@
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use super::client::FfeClient;
+use super::departments::DepartmentLookup;
+use super::details::{EventDetail, fetch_event_detail};
+use super::events::Event;
+use super::routing::{
+    error::RoutingError,
+    geocode::GeocodeQuery,
+    route::{get_road_distance, log_routing_failure},
+    service::RoutingProvider,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TripFeasibility {
+    /// Drive there in the morning, play, and be home by the latest arrival time.
+    DayTrip,
+    /// Reachable, but only with a night away (early start, late finish or multi-day event).
+    NeedsOvernight,
+    /// Not reachable at all within the limits.
+    Infeasible,
+}
+
+/// Limits for a same-day round trip.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DayTripPolicy {
+    /// Earliest acceptable time to leave home.
+    pub earliest_departure: NaiveTime,
+    /// Latest acceptable time to be back home; a time before the event's start, such as
+    /// "01:00", means the night after it.
+    pub latest_arrival_home: NaiveTime,
+    /// Time to be on site before the start (check-in).
+    pub arrival_margin_minutes: i64,
+    /// Start time assumed when the detail page doesn't give one.
+    pub default_start: NaiveTime,
+    /// Finish time assumed when it can't be estimated from the detail page.
+    pub default_finish: NaiveTime,
+    /// Beyond this one-way drive, the event is infeasible even with an overnight stay.
+    pub max_one_way_hours: f64,
+}
+
+impl Default for DayTripPolicy {
+    fn default() -> Self {
+        Self {
+            earliest_departure: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            latest_arrival_home: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+            arrival_margin_minutes: 30,
+            default_start: NaiveTime::from_hms_opt(9, 30, 0).unwrap(),
+            default_finish: NaiveTime::from_hms_opt(19, 0, 0).unwrap(),
+            max_one_way_hours: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayTripAssessment {
+    pub event: Event,
+    pub feasibility: TripFeasibility,
+    /// Human-readable explanation of the classification.
+    pub reason: String,
+    pub outbound_hours: Option<f64>,
+    pub return_hours: Option<f64>,
+    pub start_time: NaiveTime,
+    pub estimated_finish: NaiveTime,
+    pub departure: Option<NaiveDateTime>,
+    pub arrival_home: Option<NaiveDateTime>,
+}
+
+/// Classifies one event, routing `origin -> event`, then `event -> origin` if the event
+/// can be reached.
+///
+/// Routing failures make the event infeasible; only a used-up ORS quota is returned
+/// as an error, since no further event can be assessed.
+pub fn assess_day_trip(
+    origin_query: &GeocodeQuery,
+    event: &Event,
+    detail: Option<&EventDetail>,
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+    policy: &DayTripPolicy,
+) -> Result<DayTripAssessment, RoutingError> {
+    let start_time = detail
+        .and_then(|d| d.start_time)
+        .unwrap_or(policy.default_start);
+    let estimated_finish = detail
+        .and_then(EventDetail::estimated_finish)
+        .unwrap_or(policy.default_finish);
+    let mut assessment = DayTripAssessment {
+        event: event.clone(),
+        feasibility: TripFeasibility::Infeasible,
+        reason: String::new(),
+        outbound_hours: None,
+        return_hours: None,
+        start_time,
+        estimated_finish,
+        departure: None,
+        arrival_home: None,
+    };
+
+    let Some(destination) = lookup.build_geocode_query(&event.location, &event.department) else {
+        assessment.reason = format!("unknown department {}", event.department);
+        return Ok(assessment);
+    };
+    let outbound = match get_road_distance(origin_query, &destination, provider) {
+        Ok(route) => route.duration_hours,
+        Err(e) if e.is_quota_exhausted() => return Err(e),
+        Err(e) => {
+            log_routing_failure(origin_query, &destination, &e);
+            assessment.reason = "no route to the event".to_string();
+            return Ok(assessment);
+        }
+    };
+    let inbound = match get_road_distance(&destination, origin_query, provider) {
+        Ok(route) => route.duration_hours,
+        Err(e) if e.is_quota_exhausted() => return Err(e),
+        Err(e) => {
+            log_routing_failure(&destination, origin_query, &e);
+            assessment.reason = "no route back home".to_string();
+            return Ok(assessment);
+        }
+    };
+    assessment.outbound_hours = Some(outbound);
+    assessment.return_hours = Some(inbound);
+
+    if outbound.max(inbound) > policy.max_one_way_hours {
+        assessment.reason = format!(
+            "{:.2} hrs one way exceeds {:.2}",
+            outbound.max(inbound),
+            policy.max_one_way_hours
+        );
+        return Ok(assessment);
+    }
+
+    let departure = event.start_date.and_time(start_time)
+        - Duration::minutes(policy.arrival_margin_minutes)
+        - hours(outbound);
+    let arrival_home = event.end_date.and_time(estimated_finish) + hours(inbound);
+    assessment.departure = Some(departure);
+    assessment.arrival_home = Some(arrival_home);
+
+    let earliest = event.start_date.and_time(policy.earliest_departure);
+    let mut latest = event.start_date.and_time(policy.latest_arrival_home);
+    if policy.latest_arrival_home < start_time {
+        latest += Duration::days(1);
+    }
+    (assessment.feasibility, assessment.reason) = if event.end_date > event.start_date {
+        let days = (event.end_date - event.start_date).num_days() + 1;
+        (
+            TripFeasibility::NeedsOvernight,
+            format!("event runs over {} days", days),
+        )
+    } else if departure < earliest {
+        (
+            TripFeasibility::NeedsOvernight,
+            format!("would leave at {}", departure.format("%H:%M")),
+        )
+    } else if arrival_home > latest {
+        (
+            TripFeasibility::NeedsOvernight,
+            format!("would be home at {}", arrival_home.format("%d/%m %H:%M")),
+        )
+    } else {
+        (
+            TripFeasibility::DayTrip,
+            format!(
+                "leave {}, home {}",
+                departure.format("%H:%M"),
+                arrival_home.format("%H:%M")
+            ),
+        )
+    };
+    Ok(assessment)
+}
+
+/// Classifies every event, fetching detail pages for start and finish times.
+/// Events without a detail page fall back to the policy's default times.
+pub fn assess_day_trips(
+    origin_query: &GeocodeQuery,
+    events: &[Event],
+    client: &FfeClient,
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+    policy: &DayTripPolicy,
+) -> Vec<DayTripAssessment> {
+    log::info!(
+        "Checking same-day round trips for {} events...",
+        events.len()
+    );
+    let mut assessments = Vec::with_capacity(events.len());
+    for event in events {
+        match assess_event(origin_query, event, client, lookup, provider, policy) {
+            Ok(assessment) => {
+                log::info!(
+                    "[{:?}] {} at {}: {}",
+                    assessment.feasibility,
+                    event.title,
+                    event.location,
+                    assessment.reason
+                );
+                assessments.push(assessment);
+            }
+            Err(e) => {
+                log::warn!("Stopping early, results are partial: {}", e);
+                break;
+            }
+        }
+    }
+    assessments
+}
+
+fn assess_event(
+    origin_query: &GeocodeQuery,
+    event: &Event,
+    client: &FfeClient,
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+    policy: &DayTripPolicy,
+) -> Result<DayTripAssessment, RoutingError> {
+    // Route first: detail pages are only worth fetching for reachable events.
+    let assessment = assess_day_trip(origin_query, event, None, lookup, provider, policy)?;
+    if assessment.feasibility == TripFeasibility::Infeasible {
+        return Ok(assessment);
+    }
+    match fetch_event_detail(client, event.id) {
+        Ok(detail) => {
+            let mut event = event.clone();
+            event.apply_detail(&detail);
+            assess_day_trip(
+                origin_query,
+                &event,
+                Some(&detail),
+                lookup,
+                provider,
+                policy,
+            )
+        }
+        Err(e) => {
+            log::warn!("No detail page for {}: {}", event.title, e);
+            Ok(assessment)
+        }
+    }
+}
+
+fn hours(value: f64) -> Duration {
+    Duration::seconds((value * 3600.0).round() as i64)
+}
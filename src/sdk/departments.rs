@@ -1,50 +1,347 @@
 use csv::ReaderBuilder;
-use std::{collections::HashMap, error::Error, fs::File, path::Path};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    fs::File,
+    io::Read,
+    path::Path,
+};
+
+use super::error::ConfigError;
+use super::routing::cache::Coord;
+use super::routing::geocode::{DepartmentHint, GeocodeQuery};
+use super::routing::geojson::parse_feature_collection;
+use super::routing::geometry::Area;
+
+/// The department table compiled into the binary: number, name, region, prefecture,
+/// centroid latitude and longitude, and space-separated neighbouring departments.
+const EMBEDDED_CSV: &str = include_str!("../departments.csv");
+
+/// A department and where it sits in the country.
+#[derive(Debug, Clone, Serialize)]
+pub struct Department {
+    pub code: String,
+    pub name: String,
+    /// The geography fields are empty when loaded from a 2-column (number, name) file.
+    pub region: Option<String>,
+    pub prefecture: Option<String>,
+    /// `(lon, lat)` of the department's geographic centre.
+    pub centroid: Option<Coord>,
+    /// Departments sharing a land border.
+    pub neighbours: Vec<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct DepartmentLookup {
-    departments: HashMap<String, String>,
+    departments: HashMap<String, Department>,
+    /// Outlines by department code, when a boundaries file was loaded.
+    boundaries: HashMap<String, Area>,
 }
 
 impl DepartmentLookup {
-    /// Creates a new lookup table from a 2-column CSV file (number, name).
-    pub fn new<P: AsRef<Path>>(csv_path: P) -> Result<Self, Box<dyn Error>> {
-        let file = File::open(csv_path)?;
-        let mut rdr = ReaderBuilder::new().delimiter(b',').from_reader(file);
+    /// The department table embedded at compile time, with full geography.
+    pub fn embedded() -> Self {
+        Self::from_reader(EMBEDDED_CSV.as_bytes(), Path::new("departments.csv"))
+            .expect("embedded departments.csv is valid")
+    }
+
+    /// Creates a new lookup table from a CSV file with the same columns as the embedded
+    /// one, or just the first two (number, name).
+    pub fn new<P: AsRef<Path>>(csv_path: P) -> Result<Self, ConfigError> {
+        let path = csv_path.as_ref();
+        let file = File::open(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_reader(file, path)
+    }
+
+    fn from_reader<R: Read>(reader: R, path: &Path) -> Result<Self, ConfigError> {
+        let data_error = |message: String| ConfigError::Data {
+            path: path.to_path_buf(),
+            message,
+        };
+        let mut rdr = ReaderBuilder::new()
+            .delimiter(b',')
+            .flexible(true)
+            .from_reader(reader);
 
         let mut departments = HashMap::new();
         for result in rdr.records() {
-            let record = result?;
+            let record = result.map_err(|e| data_error(e.to_string()))?;
             // Using .get(index) is safer than unwrapping
             let number = record
                 .get(0)
-                .ok_or("Missing department number in CSV")?
+                .ok_or_else(|| data_error("Missing department number in CSV".to_string()))?
                 .trim()
                 .to_string();
             let name = record
                 .get(1)
-                .ok_or("Missing department name in CSV")?
+                .ok_or_else(|| data_error("Missing department name in CSV".to_string()))?
                 .trim()
                 .to_string();
-            departments.insert(number, name);
+            let field = |i: usize| {
+                record
+                    .get(i)
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+            };
+            let coordinate = |i: usize| {
+                field(i)
+                    .map(|v| {
+                        v.parse::<f64>().map_err(|_| {
+                            data_error(format!("Invalid coordinate '{}' for {}", v, number))
+                        })
+                    })
+                    .transpose()
+            };
+            let centroid = match (coordinate(4)?, coordinate(5)?) {
+                (Some(lat), Some(lon)) => Some((lon, lat)),
+                _ => None,
+            };
+            let neighbours = field(6)
+                .map(|v| v.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default();
+            let department = Department {
+                code: number.clone(),
+                name,
+                region: field(2),
+                prefecture: field(3),
+                centroid,
+                neighbours,
+            };
+            departments.insert(number, department);
+        }
+
+        Ok(DepartmentLookup {
+            departments,
+            boundaries: HashMap::new(),
+        })
+    }
+
+    /// Loads department outlines from a GeoJSON `FeatureCollection` whose features carry
+    /// the department number in a `code` property (as in the `france-geojson` files).
+    pub fn with_boundaries<P: AsRef<Path>>(mut self, geojson_path: P) -> Result<Self, ConfigError> {
+        let path = geojson_path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let areas =
+            parse_feature_collection(&text, "code").map_err(|message| ConfigError::Data {
+                path: path.to_path_buf(),
+                message,
+            })?;
+        for area in areas {
+            if !self.is_valid_department(&area.name) {
+                log::warn!("Boundary for unknown department {} ignored", area.name);
+                continue;
+            }
+            self.boundaries.insert(area.name.clone(), area);
         }
+        log::info!(
+            "Loaded boundaries of {}/{} departments",
+            self.boundaries.len(),
+            self.departments.len()
+        );
+        Ok(self)
+    }
+
+    pub fn has_boundaries(&self) -> bool {
+        !self.boundaries.is_empty()
+    }
+
+    /// Whether `coord` lies in the department; `None` when its outline isn't loaded.
+    pub fn contains(&self, dept_code: &str, coord: Coord) -> Option<bool> {
+        self.boundaries
+            .get(dept_code)
+            .map(|area| area.contains(coord))
+    }
+
+    /// The department whose outline contains `coord`.
+    pub fn department_at(&self, coord: Coord) -> Option<&str> {
+        self.boundaries
+            .values()
+            .find(|area| area.contains(coord))
+            .map(|area| area.name.as_str())
+    }
+
+    /// Gets the number of a department from its full name (e.g., "Ille-et-Vilaine" -> "35").
+    pub fn get_code(&self, name: &str) -> Option<&String> {
+        self.departments
+            .values()
+            .find(|d| d.name == name)
+            .map(|d| &d.code)
+    }
 
-        Ok(DepartmentLookup { departments })
+    /// The department of a query made by `build_geocode_query` ("City, Department").
+    pub fn department_from_query(&self, query: &str) -> Option<&String> {
+        let (_, dept_name) = query.rsplit_once(", ")?;
+        self.get_code(dept_name)
     }
 
     /// Gets the full name of a department from its number (e.g., "35" -> "Ille-et-Vilaine").
     pub fn get_name(&self, number: &str) -> Option<&String> {
+        self.departments.get(number).map(|d| &d.name)
+    }
+
+    pub fn get(&self, number: &str) -> Option<&Department> {
         self.departments.get(number)
     }
 
+    /// Centre of the department, as `(lon, lat)`.
+    pub fn centroid(&self, number: &str) -> Option<Coord> {
+        self.get(number).and_then(|d| d.centroid)
+    }
+
+    /// Departments sharing a land border with this one.
+    pub fn neighbours(&self, number: &str) -> &[String] {
+        self.get(number).map_or(&[], |d| d.neighbours.as_slice())
+    }
+
+    /// Region names, sorted.
+    pub fn regions(&self) -> Vec<&str> {
+        let mut regions: Vec<&str> = self
+            .departments
+            .values()
+            .filter_map(|d| d.region.as_deref())
+            .collect();
+        regions.sort_unstable();
+        regions.dedup();
+        regions
+    }
+
+    /// Departments of a region, sorted by number. The name is matched ignoring case,
+    /// accents and punctuation ("ile de france" finds "Île-de-France").
+    pub fn in_region(&self, region: &str) -> Vec<&Department> {
+        let region = normalize_name(region);
+        let mut departments: Vec<&Department> = self
+            .departments
+            .values()
+            .filter(|d| {
+                d.region
+                    .as_deref()
+                    .is_some_and(|r| normalize_name(r) == region)
+            })
+            .collect();
+        departments.sort_by(|a, b| a.code.cmp(&b.code));
+        departments
+    }
+
+    /// Departments at most `max_hops` borders away, with their distance in hops
+    /// (0 for the department itself), sorted by distance then number.
+    pub fn within_hops(&self, number: &str, max_hops: u32) -> Vec<(&Department, u32)> {
+        let Some(start) = self.get(number) else {
+            return Vec::new();
+        };
+        let mut hops: HashMap<&str, u32> = HashMap::from([(start.code.as_str(), 0)]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((department, distance)) = queue.pop_front() {
+            if distance == max_hops {
+                continue;
+            }
+            for neighbour in department.neighbours.iter().filter_map(|n| self.get(n)) {
+                if !hops.contains_key(neighbour.code.as_str()) {
+                    hops.insert(&neighbour.code, distance + 1);
+                    queue.push_back((neighbour, distance + 1));
+                }
+            }
+        }
+        let mut found: Vec<(&Department, u32)> = hops
+            .into_iter()
+            .filter_map(|(code, distance)| self.get(code).map(|d| (d, distance)))
+            .collect();
+        found.sort_by(|(a, da), (b, db)| da.cmp(db).then_with(|| a.code.cmp(&b.code)));
+        found
+    }
+
     /// Checks if a department number is valid.
     pub fn is_valid_department(&self, number: &str) -> bool {
         self.departments.contains_key(number)
     }
 
-    /// Builds a full location string suitable for geocoding (e.g., "City, Department, France").
-    pub fn build_geocode_query(&self, city: &str, dept_code: &str) -> Option<String> {
-        self.get_name(dept_code)
-            .map(|dept_name| format!("{}, {}", city, dept_name))
+    /// Builds a geocode query for a city in a department: the text "City, Department" and
+    /// the department the result must lie in.
+    pub fn build_geocode_query(&self, city: &str, dept_code: &str) -> Option<GeocodeQuery> {
+        let department = self.department_hint(dept_code)?;
+        Some(GeocodeQuery {
+            text: format!("{}, {}", city, department.name),
+            department: Some(department),
+        })
+    }
+
+    /// What the geocoder needs to narrow a search to the department.
+    pub fn department_hint(&self, dept_code: &str) -> Option<DepartmentHint> {
+        let department = self.get(dept_code)?;
+        Some(DepartmentHint {
+            code: department.code.clone(),
+            name: department.name.clone(),
+            centroid: department.centroid,
+            bbox: self.boundaries.get(dept_code).and_then(Area::bbox),
+        })
     }
+
+    /// Infers the department from a French postal code (e.g., "35500" -> "35").
+    ///
+    /// Corsican codes start with "20" ("200xx"/"201xx" for Corse-du-Sud, "202xx"/"206xx"
+    /// for Haute-Corse) and overseas codes use three digits ("97411" -> "974").
+    pub fn department_from_postal_code(&self, postal_code: &str) -> Option<String> {
+        let code = postal_code.trim();
+        if code.len() != 5 || !code.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let department = match &code[..3] {
+            "200" | "201" => "2A",
+            "202" | "206" => "2B",
+            prefix if prefix.starts_with("97") => prefix,
+            _ => &code[..2],
+        };
+        self.is_valid_department(department)
+            .then(|| department.to_string())
+    }
+
+    /// Infers the department from an INSEE commune code (e.g., "35238" -> "35", "2A004" -> "2A").
+    pub fn department_from_insee(&self, insee_code: &str) -> Option<String> {
+        let code = insee_code.trim().to_ascii_uppercase();
+        if code.len() != 5 || !code.is_ascii() {
+            return None;
+        }
+        let department = if code.starts_with("97") {
+            &code[..3]
+        } else {
+            &code[..2]
+        };
+        if !code[2..].chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        self.is_valid_department(department)
+            .then(|| department.to_string())
+    }
+}
+
+impl Default for DepartmentLookup {
+    fn default() -> Self {
+        Self::embedded()
+    }
+}
+
+/// Lowercases and strips accents and punctuation, so that "Côtes-d'Armor" and
+/// "Cotes d Armor" compare equal.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            'à' | 'â' | 'ä' => Some('a'),
+            'é' | 'è' | 'ê' | 'ë' => Some('e'),
+            'î' | 'ï' => Some('i'),
+            'ô' | 'ö' => Some('o'),
+            'ù' | 'û' | 'ü' => Some('u'),
+            'ç' => Some('c'),
+            'ÿ' => Some('y'),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
 }
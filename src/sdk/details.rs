@@ -0,0 +1,267 @@
+use chrono::NaiveTime;
+use scraper::{Html, Selector};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::client::FfeClient;
+use super::eligibility::RatingConstraint;
+use super::error::ScrapeError;
+use super::events::{Event, EventId};
+use super::status::EventStatus;
+
+/// Information from an event's detail page (`FicheTournoi.aspx?Ref=...`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EventDetail {
+    /// Raw time control, e.g. "15 min + 3 s".
+    pub cadence: Option<String>,
+    pub rounds: Option<u32>,
+    /// Raw schedule text, e.g. "Pointage 9h00, ronde 1 à 9h30 ... remise des prix 18h30".
+    pub schedule: Option<String>,
+    /// First time found in the schedule.
+    pub start_time: Option<NaiveTime>,
+    /// Last time found in the schedule, usually the prize-giving.
+    pub end_time: Option<NaiveTime>,
+    /// Cancelled or postponed when the tournament's name or state says so.
+    pub status: EventStatus,
+    /// Elo limits found in the name or the entry conditions.
+    pub rating_constraint: Option<RatingConstraint>,
+}
+
+/// A parsed time control: base time per player plus increment per move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Cadence {
+    pub base_minutes: f64,
+    pub increment_seconds: f64,
+}
+
+impl Cadence {
+    /// Parses FFE cadence strings such as "15 min + 3 s", "1h30 + 30s" or "90' + 30''".
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.to_lowercase().replace(' ', "");
+        let (base, increment) = match text.split_once('+') {
+            Some((base, increment)) => (base, Some(increment)),
+            None => (text.as_str(), None),
+        };
+
+        let base_minutes = if let Some((hours, minutes)) = base.split_once('h') {
+            let hours: f64 = leading_number(hours)?;
+            hours * 60.0 + leading_number(minutes).unwrap_or(0.0)
+        } else {
+            leading_number(base)?
+        };
+        let increment_seconds = increment.and_then(leading_number).unwrap_or(0.0);
+        Some(Self {
+            base_minutes,
+            increment_seconds,
+        })
+    }
+
+    /// Expected length of one game, assuming 40 moves and both clocks used up.
+    pub fn estimated_game_minutes(&self) -> f64 {
+        2.0 * (self.base_minutes + 40.0 * self.increment_seconds / 60.0)
+    }
+}
+
+fn leading_number(text: &str) -> Option<f64> {
+    let digits: String = text.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+impl EventDetail {
+    /// Estimated finish: the last scheduled time, or start plus all rounds at the cadence.
+    pub fn estimated_finish(&self) -> Option<NaiveTime> {
+        let start = self.start_time?;
+        if let Some(end) = self.end_time.filter(|end| *end > start) {
+            return Some(end);
+        }
+        let rounds = self.rounds?;
+        let cadence = Cadence::parse(self.cadence.as_deref()?)?;
+        let minutes = cadence.estimated_game_minutes() * rounds as f64;
+        let (finish, overflow) =
+            start.overflowing_add_signed(chrono::Duration::minutes(minutes.round() as i64));
+        (overflow == 0).then_some(finish)
+    }
+}
+
+/// Extracts every time written like "9h", "9h30", "09:30" or "14H00", in order.
+pub fn parse_times(text: &str) -> Vec<NaiveTime> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut times = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() || (i > 0 && chars[i - 1].is_ascii_digit()) {
+            i += 1;
+            continue;
+        }
+        let mut j = i;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        let hours: String = chars[i..j].iter().collect();
+        if j - i <= 2 && j < chars.len() && matches!(chars[j], 'h' | 'H' | ':') {
+            let mut k = j + 1;
+            while k < chars.len() && k < j + 3 && chars[k].is_ascii_digit() {
+                k += 1;
+            }
+            let minutes: String = chars[j + 1..k].iter().collect();
+            let is_colon = chars[j] == ':';
+            if (!is_colon || minutes.len() == 2)
+                && let (Ok(h), Ok(m)) = (hours.parse(), minutes.parse().or(Ok::<u32, ()>(0)))
+                && let Some(time) = NaiveTime::from_hms_opt(h, m, 0)
+            {
+                times.push(time);
+            }
+            i = k;
+        } else {
+            i = j;
+        }
+    }
+    times
+}
+
+/// Collects "label -> value" pairs from the detail page.
+///
+/// The page lays fields out as two-cell table rows ("Cadence :" | "15 min + 3 s"),
+/// and also exposes some of them as `<span id="..._LabelCadence">` elements.
+pub(crate) fn collect_fields(document: &Html) -> HashMap<String, String> {
+    let row_selector = Selector::parse("tr").unwrap();
+    let td_selector = Selector::parse("td").unwrap();
+    let span_selector = Selector::parse("span[id]").unwrap();
+    let mut fields = HashMap::new();
+
+    for row in document.select(&row_selector) {
+        let tds: Vec<_> = row.select(&td_selector).collect();
+        if tds.len() < 2 {
+            continue;
+        }
+        let label = tds[0].text().collect::<String>();
+        let label = label.trim().trim_end_matches(':').trim().to_lowercase();
+        let value = tds[1..]
+            .iter()
+            .map(|td| td.text().collect::<String>())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !label.is_empty() && !value.is_empty() {
+            fields.entry(label).or_insert(value);
+        }
+    }
+
+    for span in document.select(&span_selector) {
+        let Some(id) = span.value().attr("id") else {
+            continue;
+        };
+        if let Some((_, name)) = id.rsplit_once("Label") {
+            let value = span.text().collect::<Vec<_>>().join(" ");
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !value.is_empty() {
+                fields.entry(name.to_lowercase()).or_insert(value);
+            }
+        }
+    }
+    fields
+}
+
+pub(crate) fn field<'a>(fields: &'a HashMap<String, String>, names: &[&str]) -> Option<&'a str> {
+    names
+        .iter()
+        .find_map(|name| fields.get(*name))
+        .map(String::as_str)
+}
+
+/// Parses the HTML of an event's detail page.
+pub fn parse_event_detail_html(html: &str) -> EventDetail {
+    let document = Html::parse_document(html);
+    let fields = collect_fields(&document);
+
+    let cadence = field(&fields, &["cadence"]).map(str::to_string);
+    let rounds = field(&fields, &["nombre de rondes", "nbrrondes", "rondes"])
+        .and_then(leading_number)
+        .map(|n| n as u32);
+    let schedule = field(&fields, &["horaires", "horaire"]).map(str::to_string);
+    let times = schedule.as_deref().map(parse_times).unwrap_or_default();
+    // Only the name and state fields: the rules text may mention cancellation in general.
+    let status = ["nom", "tournoi", "statut", "etat", "état"]
+        .iter()
+        .filter_map(|name| fields.get(*name))
+        .map(|value| EventStatus::from_text(value))
+        .max()
+        .unwrap_or_default();
+
+    let rating_constraint = fields
+        .iter()
+        .filter(|(label, _)| {
+            *label == "nom"
+                || ["elo", "restriction", "condition"]
+                    .iter()
+                    .any(|word| label.contains(word))
+        })
+        .filter_map(|(_, value)| RatingConstraint::from_text(value))
+        .reduce(|a, b| RatingConstraint {
+            min: a.min.max(b.min),
+            max: match (a.max, b.max) {
+                (Some(x), Some(y)) => Some(x.min(y)),
+                (x, y) => x.or(y),
+            },
+        });
+
+    EventDetail {
+        cadence,
+        rounds,
+        schedule,
+        start_time: times.first().copied(),
+        end_time: times.iter().max().copied(),
+        status,
+        rating_constraint,
+    }
+}
+
+/// Fetches and parses the detail page of the event with this id.
+pub fn fetch_event_detail(client: &FfeClient, id: EventId) -> Result<EventDetail, ScrapeError> {
+    let url = id.detail_url();
+    log::debug!("Fetching event detail from {}", url);
+    let html = client.fetch_page(&url)?;
+    Ok(parse_event_detail_html(&html))
+}
+
+/// Fetches the detail page of each event not read yet this run and refines the event from
+/// it: status, time control and Elo limits.
+pub fn refine_from_details<'a>(
+    events: impl IntoIterator<Item = &'a mut Event>,
+    client: &FfeClient,
+) {
+    for event in events.into_iter().filter(|e| !e.detail_checked) {
+        match fetch_event_detail(client, event.id) {
+            Ok(detail) => {
+                event.apply_detail(&detail);
+                log::debug!(
+                    "{}: {:?}, {:?}, {:?}",
+                    event.title,
+                    event.status,
+                    event.time_control,
+                    event.rating_constraint
+                );
+            }
+            Err(e) => log::warn!("No detail page for {}: {}", event.title, e),
+        }
+    }
+}
+
+/// Fetches the detail page of the events picked by `wanted` whose title names a bracket
+/// ("Open A") without its Elo limits, and takes the limits from the page.
+pub fn refine_rating_constraints(
+    events: &mut [Event],
+    client: &FfeClient,
+    wanted: impl Fn(&Event) -> bool,
+) {
+    let unknown: Vec<&mut Event> = events
+        .iter_mut()
+        .filter(|e| {
+            e.rating_constraint.is_none()
+                && RatingConstraint::title_hints_bracket(&e.title)
+                && wanted(e)
+        })
+        .collect();
+    log::info!("Fetching {} detail pages for Elo limits...", unknown.len());
+    refine_from_details(unknown, client);
+}
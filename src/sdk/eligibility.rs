@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+
+use super::departments::normalize_name;
+use super::events::Event;
+use super::players::PlayerProfile;
+use super::time_control::TimeControl;
+
+/// Numbers outside this range are years, fees or round counts, not Elo limits.
+const ELO_RANGE: std::ops::RangeInclusive<u32> = 1000..=2900;
+
+/// Numbers in this range that aren't a multiple of 50 read as years ("Open de Noël - 2025"),
+/// unless a comparison names them as a limit ("< 2025").
+const YEAR_RANGE: std::ops::RangeInclusive<u32> = 1950..=2100;
+
+fn is_year(n: u32) -> bool {
+    YEAR_RANGE.contains(&n) && !n.is_multiple_of(50)
+}
+
+/// The Elo bracket of an event, e.g. "Open A > 1800" or "Open B < 1600".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatingConstraint {
+    /// Lowest Elo allowed, inclusive.
+    pub min: Option<u32>,
+    /// Highest Elo allowed, inclusive.
+    pub max: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(u32),
+    Word(String),
+    Op(String),
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                number.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Number(number.parse().unwrap_or(0)));
+        } else if c.is_alphabetic() {
+            let mut word = String::new();
+            while let Some(&l) = chars.peek().filter(|l| l.is_alphabetic()) {
+                word.push(l);
+                chars.next();
+            }
+            tokens.push(Token::Word(normalize_name(&word)));
+        } else {
+            chars.next();
+            let op = match c {
+                '≥' => ">=".to_string(),
+                '≤' => "<=".to_string(),
+                '<' | '>' if chars.peek() == Some(&'=') => {
+                    chars.next();
+                    format!("{}=", c)
+                }
+                '<' | '>' | '+' | '-' => c.to_string(),
+                _ => continue,
+            };
+            tokens.push(Token::Op(op));
+        }
+    }
+    tokens
+}
+
+impl RatingConstraint {
+    /// Reads Elo limits from a title or a detail page field: "> 1800", "<1600", "≤ 2000",
+    /// "-1600", "moins de 1600", "plus de 1800", "U1600", "1800+", "1800 et +", "1400-1800" and
+    /// "entre 1400 et 1800". Returns `None` when the text sets no limit. Years are skipped,
+    /// except after an explicit comparison.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let tokens = tokenize(text);
+        // After "<", ">", "moins de"...: any number in the Elo range.
+        let bound = |i: usize| match tokens.get(i) {
+            Some(Token::Number(n)) if ELO_RANGE.contains(n) => Some(*n),
+            _ => None,
+        };
+        // Anywhere else: not a year.
+        let elo = |i: usize| bound(i).filter(|n| !is_year(*n));
+        let is_word = |i: usize, w: &str| matches!(tokens.get(i), Some(Token::Word(x)) if x == w);
+        let is_op = |i: usize, o: &str| matches!(tokens.get(i), Some(Token::Op(x)) if x == o);
+        // "-1600" means under 1600, but not in "1800-1600"; "2025 -1600" still does.
+        let is_minus_sign = |i: usize| {
+            is_op(i, "-")
+                && !matches!(
+                    i.checked_sub(1).map(|j| &tokens[j]),
+                    Some(Token::Number(n)) if !is_year(*n)
+                )
+        };
+
+        let mut constraint = Self::default();
+        let mut i = 0;
+        while i < tokens.len() {
+            let (min, max, used) = if let Some(n) = bound(i + 1).filter(|_| is_op(i, ">")) {
+                (Some(n + 1), None, 2)
+            } else if let Some(n) = bound(i + 1).filter(|_| is_op(i, ">=")) {
+                (Some(n), None, 2)
+            } else if let Some(n) = elo(i + 1).filter(|_| is_op(i, "+")) {
+                (Some(n), None, 2)
+            } else if let Some(n) = bound(i + 1).filter(|_| is_op(i, "<")) {
+                (None, Some(n - 1), 2)
+            } else if let Some(n) = elo(i + 1).filter(|_| is_minus_sign(i) || is_word(i, "u")) {
+                (None, Some(n - 1), 2)
+            } else if let Some(n) = bound(i + 1).filter(|_| is_op(i, "<=")) {
+                (None, Some(n), 2)
+            } else if let Some(n) =
+                bound(i + 2).filter(|_| is_word(i, "moins") && is_word(i + 1, "de"))
+            {
+                (None, Some(n - 1), 3)
+            } else if let Some(n) =
+                bound(i + 2).filter(|_| is_word(i, "plus") && is_word(i + 1, "de"))
+            {
+                (Some(n + 1), None, 3)
+            } else if let (Some(a), Some(b)) = (elo(i + 1), elo(i + 3))
+                && is_word(i, "entre")
+                && is_word(i + 2, "et")
+                && a + 100 <= b
+            {
+                (Some(a), Some(b), 4)
+            } else if let (Some(a), Some(b)) = (elo(i), elo(i + 2))
+                && is_op(i + 1, "-")
+                && a + 100 <= b
+            {
+                (Some(a), Some(b), 3)
+            } else if let Some(n) = elo(i).filter(|_| is_op(i + 1, "+")) {
+                (Some(n), None, 2)
+            } else if let Some(n) = elo(i).filter(|_| is_word(i + 1, "et") && is_op(i + 2, "+")) {
+                (Some(n), None, 3)
+            } else {
+                (None, None, 1)
+            };
+            if let Some(min) = min {
+                constraint.min = Some(constraint.min.map_or(min, |m| m.max(min)));
+            }
+            if let Some(max) = max {
+                constraint.max = Some(constraint.max.map_or(max, |m| m.min(max)));
+            }
+            i += used;
+        }
+        (constraint != Self::default()).then_some(constraint)
+    }
+
+    /// Whether a title names a bracket ("Open A", "Tournoi B", "Accession") without
+    /// giving its limits, which are then only on the detail page.
+    pub fn title_hints_bracket(title: &str) -> bool {
+        let words: Vec<String> = title
+            .split(|c: char| !c.is_alphanumeric())
+            .map(normalize_name)
+            .filter(|w| !w.is_empty())
+            .collect();
+        words.iter().any(|w| w == "accession")
+            || words.windows(2).any(|pair| {
+                matches!(pair[0].as_str(), "open" | "tournoi" | "groupe")
+                    && matches!(pair[1].as_str(), "a" | "b" | "c" | "d" | "e")
+            })
+    }
+
+    pub fn allows(&self, elo: u32) -> bool {
+        self.min.is_none_or(|min| elo >= min) && self.max.is_none_or(|max| elo <= max)
+    }
+}
+
+/// Whether a player may enter an event, given its Elo bracket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Eligibility {
+    /// No bracket, or the player's Elo is inside it.
+    Eligible,
+    Ineligible {
+        elo: u32,
+    },
+    /// The event has a bracket but the player has no rating of that kind.
+    Unknown,
+}
+
+impl Eligibility {
+    /// Checks the player's Elo of the event's kind (standard for classical and unknown
+    /// events) against `constraint`.
+    pub fn check(
+        profile: &PlayerProfile,
+        time_control: &TimeControl,
+        constraint: Option<&RatingConstraint>,
+    ) -> Self {
+        let Some(constraint) = constraint else {
+            return Eligibility::Eligible;
+        };
+        match profile.rating_for(time_control) {
+            Some(rating) if constraint.allows(rating.elo) => Eligibility::Eligible,
+            Some(rating) => Eligibility::Ineligible { elo: rating.elo },
+            None => Eligibility::Unknown,
+        }
+    }
+}
+
+/// The player's eligibility for `event`, if it has an Elo bracket.
+pub fn eligibility_for(event: &Event, profile: &PlayerProfile) -> Option<Eligibility> {
+    event
+        .rating_constraint
+        .as_ref()
+        .map(|constraint| Eligibility::check(profile, &event.time_control, Some(constraint)))
+}
+
+/// Marks the events that have an Elo bracket with the player's eligibility.
+pub fn mark_eligibility(events: &mut [Event], profile: &PlayerProfile) {
+    let mut ineligible = 0;
+    for event in events.iter_mut() {
+        event.eligibility = eligibility_for(event, profile);
+        if matches!(event.eligibility, Some(Eligibility::Ineligible { .. })) {
+            ineligible += 1;
+        }
+    }
+    log::info!(
+        "{} events have an Elo bracket the player is outside of",
+        ineligible
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The (min, max) Elo read from `text`, `None` when it has no limit.
+    fn limits(text: &str) -> Option<(Option<u32>, Option<u32>)> {
+        RatingConstraint::from_text(text).map(|c| (c.min, c.max))
+    }
+
+    #[test]
+    fn reads_lower_limits() {
+        assert_eq!(limits("Open A > 1800"), Some((Some(1801), None)));
+        assert_eq!(limits("Open A >= 1800"), Some((Some(1800), None)));
+        assert_eq!(limits("Open A ≥ 1800"), Some((Some(1800), None)));
+        assert_eq!(limits("Open A 1800+"), Some((Some(1800), None)));
+        assert_eq!(limits("Open A 1800 et +"), Some((Some(1800), None)));
+        assert_eq!(limits("Réservé aux plus de 1800"), Some((Some(1801), None)));
+    }
+
+    #[test]
+    fn reads_upper_limits() {
+        assert_eq!(limits("Open B < 1600"), Some((None, Some(1599))));
+        assert_eq!(limits("Open B <1600"), Some((None, Some(1599))));
+        assert_eq!(limits("Open B ≤ 2000"), Some((None, Some(2000))));
+        assert_eq!(limits("Open B -1600"), Some((None, Some(1599))));
+        assert_eq!(limits("Tournoi U1600"), Some((None, Some(1599))));
+        assert_eq!(
+            limits("Réservé aux moins de 1600 Elo"),
+            Some((None, Some(1599)))
+        );
+    }
+
+    #[test]
+    fn reads_ranges() {
+        assert_eq!(limits("Open 1400-1800"), Some((Some(1400), Some(1800))));
+        assert_eq!(
+            limits("Elo entre 1400 et 1800"),
+            Some((Some(1400), Some(1800)))
+        );
+        assert_eq!(
+            limits("Open A > 1800 < 2200"),
+            Some((Some(1801), Some(2199)))
+        );
+    }
+
+    #[test]
+    fn ignores_numbers_that_are_not_limits() {
+        assert_eq!(limits("Open de Vitré"), None);
+        assert_eq!(limits("Open A"), None);
+        assert_eq!(limits("Open < 500"), None, "below any Elo");
+        assert_eq!(limits("Droits 15 €, 9 rondes"), None);
+    }
+
+    #[test]
+    fn years_are_not_elo_limits() {
+        assert_eq!(limits("Open de Noël - 2025"), None);
+        assert_eq!(limits("Championnat Bretagne TC 2025"), None);
+        assert_eq!(limits("Saison 2024-2025"), None);
+        assert_eq!(limits("Open 2025 +"), None);
+        assert_eq!(limits("Rapide de Dinan 2024 et +"), None);
+
+        // A year next to a real limit, or a limit that looks like a year.
+        assert_eq!(limits("Open B - 2025 -1600"), Some((None, Some(1599))));
+        assert_eq!(limits("Open A 2025 > 1800"), Some((Some(1801), None)));
+        assert_eq!(limits("Open B -2000"), Some((None, Some(1999))));
+        assert_eq!(limits("Open A < 2025"), Some((None, Some(2024))));
+        assert_eq!(limits("Open 2000-2400"), Some((Some(2000), Some(2400))));
+    }
+
+    #[test]
+    fn lettered_titles_hint_at_a_bracket() {
+        assert!(RatingConstraint::title_hints_bracket("Open A de Vitré"));
+        assert!(RatingConstraint::title_hints_bracket("Tournoi B"));
+        assert!(RatingConstraint::title_hints_bracket("Accession"));
+        assert!(!RatingConstraint::title_hints_bracket("Open de Vitré"));
+        assert!(!RatingConstraint::title_hints_bracket("Rapide du club"));
+    }
+
+    #[test]
+    fn limits_are_inclusive() {
+        let constraint = RatingConstraint {
+            min: Some(1400),
+            max: Some(1800),
+        };
+        assert!(!constraint.allows(1399));
+        assert!(constraint.allows(1400));
+        assert!(constraint.allows(1800));
+        assert!(!constraint.allows(1801));
+    }
+}
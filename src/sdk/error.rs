@@ -0,0 +1,148 @@
+use reqwest::StatusCode;
+use std::{io, path::PathBuf, time::Duration};
+use thiserror::Error;
+
+use super::routing::error::{
+    GeocodeError, RoutingError, is_retryable_request, is_retryable_status,
+};
+
+/// Errors from fetching or parsing FFE pages.
+#[derive(Error, Debug)]
+pub enum ScrapeError {
+    #[error("Request to {url} failed: {source}")]
+    Request {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("{url} returned {status}")]
+    Status {
+        url: String,
+        status: StatusCode,
+        /// `Retry-After` sent with the response, if any.
+        retry_after: Option<Duration>,
+    },
+
+    #[error("Unexpected page layout at {url}: {message}")]
+    Layout { url: String, message: String },
+}
+
+impl ScrapeError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ScrapeError::Request { source, .. } => is_retryable_request(source),
+            ScrapeError::Status { status, .. } => is_retryable_status(*status),
+            ScrapeError::Layout { .. } => false,
+        }
+    }
+
+    /// Delay requested by the server before trying again, if it sent one.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ScrapeError::Status { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+/// Errors from reading or writing the geocode/route cache.
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Cannot access cache file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Corrupt cache file {path}: {source}")]
+    Format {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Errors from configuration files, environment variables and bundled data.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Cannot read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    #[error("Invalid data file {path}: {message}")]
+    Data { path: PathBuf, message: String },
+
+    #[error("Missing ORS configuration: Please set ORS_LOCAL_URL and/or ORS_API_KEY")]
+    MissingProvider,
+
+    #[error(
+        "Provider mode '{0}' is missing its settings: check ORS_LOCAL_URL/ORS_API_KEY or the [provider] section"
+    )]
+    IncompleteProvider(String),
+
+    #[error("Invalid value for {name}: {value}")]
+    InvalidValue { name: String, value: String },
+
+    #[error("Unknown origin '{0}' in config file")]
+    UnknownOrigin(String),
+
+    #[error("Invalid origin: {0}")]
+    InvalidOrigin(String),
+}
+
+/// Errors from writing scraped data to CSV.
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Cannot write {path}: {source}")]
+    Csv {
+        path: PathBuf,
+        #[source]
+        source: csv::Error,
+    },
+}
+
+/// Any error returned by the SDK, for callers that don't care which stage failed.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Scrape(#[from] ScrapeError),
+
+    #[error(transparent)]
+    Routing(#[from] RoutingError),
+
+    #[error(transparent)]
+    Geocode(#[from] GeocodeError),
+
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error(transparent)]
+    Export(#[from] ExportError),
+}
+
+impl Error {
+    /// Whether the failed operation may succeed if attempted again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Scrape(e) => e.is_retryable(),
+            Error::Routing(e) => e.is_retryable(),
+            Error::Geocode(e) => e.is_retryable(),
+            Error::Cache(_) | Error::Config(_) | Error::Export(_) => false,
+        }
+    }
+}
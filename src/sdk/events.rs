@@ -1,34 +1,166 @@
 use chrono::{Datelike, NaiveDate};
-use reqwest::{
-    blocking::Client,
-    header::{HeaderMap, USER_AGENT},
-};
 use scraper::{Html, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
+use super::client::FfeClient;
 use super::departments::DepartmentLookup;
+use super::details::EventDetail;
+use super::eligibility::{Eligibility, RatingConstraint};
+use super::error::ScrapeError;
+use super::filters::SearchScope;
+use super::players::FieldStats;
 use super::routing::{
-    cache::GeoCache, error::RoutingError, route::get_road_distance, service::RoutingProvider,
+    error::RoutingError,
+    origin::ResolvedOrigin,
+    route::{RouteSummary, get_road_distance},
+    service::RoutingProvider,
 };
+use super::status::EventStatus;
+use super::territory::{FerryConfig, Territory, fastest_ferry_trip};
+use super::time_control::TimeControl;
+
+pub(crate) const BASE_URL: &str = "https://www.echecs.asso.fr/";
+
+/// The FFE's own number for a tournament, the `Ref` of `FicheTournoi.aspx?Ref=...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EventId(pub u64);
+
+impl EventId {
+    /// Reads the `Ref` parameter of a detail page link, relative or absolute.
+    pub fn from_link(link: &str) -> Option<Self> {
+        let (_, query) = link.split_once('?')?;
+        query
+            .split('&')
+            .find_map(|param| {
+                param
+                    .split_once('=')
+                    .filter(|(k, _)| k.eq_ignore_ascii_case("ref"))
+            })
+            .and_then(|(_, value)| value.trim().parse().ok())
+            .map(EventId)
+    }
+
+    /// The event's detail page.
+    pub fn detail_url(&self) -> String {
+        format!("{}FicheTournoi.aspx?Ref={}", BASE_URL, self.0)
+    }
+
+    /// One of the event's results pages, e.g. "Ls" for the list of registered players.
+    pub(crate) fn results_url(&self, action: &str) -> String {
+        format!(
+            "{}Resultats.aspx?URL=Tournois/Id/{id}/{id}&Action={}",
+            BASE_URL,
+            action,
+            id = self.0
+        )
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for EventId {
+    type Err = String;
 
-#[derive(Debug, Clone, Serialize, Eq, PartialEq, Hash)]
+    /// Accepts the bare number or a detail page link.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        s.parse()
+            .map(EventId)
+            .ok()
+            .or_else(|| EventId::from_link(s))
+            .ok_or_else(|| format!("Invalid FFE event Ref: {}", s))
+    }
+}
+
+/// A tournament on the FFE calendar. Two events are the same when their `id` is,
+/// whatever the rest of the listing says.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
+    pub id: EventId,
     pub title: String,
     pub department: String,
     pub location: String,
     pub start_date: NaiveDate,
     pub end_date: NaiveDate,
     pub link: String,
+    /// Guessed from the title; refined from the detail page when one is fetched.
+    pub time_control: TimeControl,
+    /// From markers in the title or on the detail page ("ANNULÉ", "REPORTÉ").
+    #[serde(default)]
+    pub status: EventStatus,
+    /// Elo limits, from the title or the detail page ("Open A > 1800").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating_constraint: Option<RatingConstraint>,
+    /// Whether the configured player may enter, once checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<Eligibility>,
+    /// Who is registered, once the list of players has been fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registered: Option<FieldStats>,
+    /// Whether the detail page was read this run, so `status` reflects it. Not saved.
+    #[serde(skip)]
+    pub detail_checked: bool,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Event {}
+
+impl Hash for Event {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Event {
+    /// Replaces the title-based time control with the detail page's cadence, if it has one.
+    pub fn refine_time_control(&mut self, detail: &EventDetail) {
+        let time_control = TimeControl::from_detail(detail);
+        if time_control != TimeControl::Unknown {
+            self.time_control = time_control;
+        }
+    }
+
+    /// Takes the status marked on the detail page or in the title, cancellation first;
+    /// with no marker on either, the event is scheduled (again).
+    pub fn refine_status(&mut self, detail: &EventDetail) {
+        self.status = [detail.status, EventStatus::from_text(&self.title)]
+            .into_iter()
+            .max()
+            .unwrap_or_default();
+    }
+
+    /// Takes the detail page's Elo limits, which are more reliable than the title's.
+    pub fn refine_rating_constraint(&mut self, detail: &EventDetail) {
+        if detail.rating_constraint.is_some() {
+            self.rating_constraint = detail.rating_constraint;
+        }
+    }
+
+    /// Refines everything the detail page tells more precisely than the calendar.
+    pub fn apply_detail(&mut self, detail: &EventDetail) {
+        self.refine_time_control(detail);
+        self.refine_status(detail);
+        self.refine_rating_constraint(detail);
+        self.detail_checked = true;
+    }
 }
 
 /// Parses the MONTHLY CALENDAR view to find which days have events.
-fn get_active_days_from_monthly_calendar(
-    html: &str,
-    month: u32,
-    year: i32,
-) -> Result<Vec<u32>, Box<dyn Error>> {
+fn get_active_days_from_monthly_calendar(html: &str, month: u32, year: i32) -> Vec<u32> {
     let document = Html::parse_document(html);
     // Each calendar day is a `<td>`. We only care about those with an `onclick` event.
     let day_cell_selector = Selector::parse("td[onclick]").unwrap();
@@ -41,33 +173,27 @@ fn get_active_days_from_monthly_calendar(
 
     for cell in document.select(&day_cell_selector) {
         // Only proceed if there's at least one event marker in the cell.
-        if cell.select(&event_marker_selector).next().is_some() {
-            if let Some(day_link) = cell.select(&day_link_selector).next() {
-                // The link's href contains the full date, e.g., 'Calendrier.aspx?jour=14/06/2025'
-                if let Some(href) = day_link.value().attr("href") {
-                    if let Some(date_str) = href.split('=').last() {
-                        if let Ok(date) = NaiveDate::parse_from_str(date_str, "%d/%m/%Y") {
-                            // Only add the day if it's in the month we're targeting.
-                            if date.month() == month && date.year() == year {
-                                active_days.insert(date.day());
-                            }
-                        }
-                    }
-                }
-            }
+        // The link's href contains the full date, e.g., 'Calendrier.aspx?jour=14/06/2025'
+        if cell.select(&event_marker_selector).next().is_some()
+            && let Some(day_link) = cell.select(&day_link_selector).next()
+            && let Some(href) = day_link.value().attr("href")
+            && let Some(date_str) = href.split('=').next_back()
+            && let Ok(date) = NaiveDate::parse_from_str(date_str, "%d/%m/%Y")
+            // Only add the day if it's in the month we're targeting.
+            && date.month() == month
+            && date.year() == year
+        {
+            active_days.insert(date.day());
         }
     }
 
     let mut sorted_days: Vec<u32> = active_days.into_iter().collect();
     sorted_days.sort_unstable();
-    Ok(sorted_days)
+    sorted_days
 }
 
 /// Parses the DETAILED LIST view for a single day.
-fn parse_list_view_html(
-    html: &str,
-    lookup: &DepartmentLookup,
-) -> Result<Vec<Event>, Box<dyn Error>> {
+fn parse_list_view_html(html: &str, lookup: &DepartmentLookup) -> Vec<Event> {
     let document = Html::parse_document(html);
     let row_selector = Selector::parse("tr.liste_clair, tr.liste_fonce").unwrap();
     let td_selector = Selector::parse("td").unwrap();
@@ -97,34 +223,45 @@ fn parse_list_view_html(
             NaiveDate::parse_from_str(&end_date_str, "%d/%m/%y")
                 .or_else(|_| NaiveDate::parse_from_str(&end_date_str, "%d/%m/%Y")),
         ) {
-            let link = title_elem
+            let href = title_elem
                 .select(&a_selector)
                 .next()
-                .and_then(|a| a.value().attr("href"))
-                .map(|href| format!("https://www.echecs.asso.fr/{}", href))
-                .unwrap_or_default();
+                .and_then(|a| a.value().attr("href"));
+            let Some(id) = href.and_then(EventId::from_link) else {
+                log::warn!(
+                    "'{}' ({}) has no FFE Ref in its link {:?}, skipped",
+                    title,
+                    location,
+                    href
+                );
+                continue;
+            };
             events.push(Event {
+                id,
+                link: format!("{}{}", BASE_URL, href.unwrap_or_default()),
+                time_control: TimeControl::from_title(&title),
+                status: EventStatus::from_text(&title),
+                rating_constraint: RatingConstraint::from_text(&title),
+                eligibility: None,
+                registered: None,
+                detail_checked: false,
                 title,
                 department,
                 location,
                 start_date,
                 end_date,
-                link,
             });
         }
     }
-    Ok(events)
+    events
 }
 
 pub fn get_events_for_month(
     month: u32,
     year: i32,
-    client: &Client,
+    client: &FfeClient,
     lookup: &DepartmentLookup,
-) -> Result<Vec<Event>, Box<dyn Error>> {
-    let mut headers = HeaderMap::new();
-    headers.insert(USER_AGENT, "Mozilla/5.0".parse().unwrap());
-
+) -> Result<Vec<Event>, ScrapeError> {
     // 1. Scout Mission: Get the monthly calendar view.
     let date_string = format!("01/{:02}/{}", month, year);
     let calendar_url = format!(
@@ -132,12 +269,8 @@ pub fn get_events_for_month(
         date_string
     );
     log::info!("Scouting for active days from {}", calendar_url);
-    let calendar_html = client
-        .get(&calendar_url)
-        .headers(headers.clone())
-        .send()?
-        .text()?;
-    let active_days = get_active_days_from_monthly_calendar(&calendar_html, month, year)?;
+    let calendar_html = client.fetch_page(&calendar_url)?;
+    let active_days = get_active_days_from_monthly_calendar(&calendar_html, month, year);
 
     if active_days.is_empty() {
         log::info!("No events found in the calendar for {}/{}", month, year);
@@ -160,13 +293,9 @@ pub fn get_events_for_month(
         );
 
         log::debug!("Fetching details from {}", list_view_url);
-        let html = client
-            .get(&list_view_url)
-            .headers(headers.clone())
-            .send()?
-            .text()?;
+        let html = client.fetch_page(&list_view_url)?;
 
-        let daily_events = parse_list_view_html(&html, lookup)?;
+        let daily_events = parse_list_view_html(&html, lookup);
         for event in daily_events {
             unique_events.insert(event);
         }
@@ -179,92 +308,364 @@ pub fn get_events_for_month(
     Ok(final_events)
 }
 
+/// The event with this id, if it is among `events`.
+pub fn find_event(events: &[Event], id: EventId) -> Option<&Event> {
+    events.iter().find(|e| e.id == id)
+}
+
+/// Fetches every event overlapping `[start, end]`, one calendar month at a time.
+pub fn get_events_for_range(
+    start: NaiveDate,
+    end: NaiveDate,
+    client: &FfeClient,
+    lookup: &DepartmentLookup,
+) -> Result<Vec<Event>, ScrapeError> {
+    let mut unique_events = HashSet::new();
+    let (mut month, mut year) = (start.month(), start.year());
+    while (year, month) <= (end.year(), end.month()) {
+        for event in get_events_for_month(month, year, client, lookup)? {
+            if event.end_date >= start && event.start_date <= end {
+                unique_events.insert(event);
+            }
+        }
+        (month, year) = if month == 12 {
+            (1, year + 1)
+        } else {
+            (month + 1, year)
+        };
+    }
+
+    let mut final_events: Vec<Event> = unique_events.into_iter().collect();
+    final_events.sort_by_key(|e| e.start_date);
+    Ok(final_events)
+}
+
+/// What `filter_reachable_events` checks, besides the events themselves.
+#[derive(Debug, Clone, Copy)]
+pub struct ReachabilityRules<'a> {
+    pub max_hours: f64,
+    /// Events outside it are excluded before any routing call.
+    pub scope: &'a SearchScope,
+    /// Crossings used for trips between the mainland and Corsica.
+    pub ferries: &'a FerryConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReachableEvent {
+    pub event: Event,
+    /// `None` when the event is in the origin's own town.
+    pub route: Option<RouteSummary>,
+    /// The crossing taken, for trips to or from Corsica.
+    pub ferry: Option<String>,
+}
+
+/// Why an event was left out of the results.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "reason")]
+pub enum ExclusionReason {
+    /// Called off, though still on the calendar.
+    Cancelled,
+    /// The player's Elo is outside the event's bracket.
+    RatingOutOfRange {
+        elo: u32,
+        constraint: RatingConstraint,
+    },
+    /// Outside the departments or regions the search is limited to.
+    OutOfScope,
+    /// Not one of the time controls searched for.
+    OtherTimeControl {
+        time_control: TimeControl,
+    },
+    /// Overseas, and not in the origin's own department.
+    NotRoadReachable {
+        territory: Territory,
+    },
+    /// The event's department is not in the department table, so it can't be geocoded.
+    UnknownDepartment {
+        department: String,
+    },
+    TooFar {
+        duration_hours: f64,
+    },
+    /// ORS refuses routes this long.
+    RouteTooLong,
+    RoutingFailed {
+        message: String,
+    },
+    /// The quota ran out before this event was checked.
+    NotChecked,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludedEvent {
+    pub event: Event,
+    #[serde(flatten)]
+    pub reason: ExclusionReason,
+}
+
+/// Events within reach, and every other event with the reason it was left out.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchResults {
+    pub reachable: Vec<ReachableEvent>,
+    pub excluded: Vec<ExcludedEvent>,
+}
+
+impl SearchResults {
+    fn exclude(&mut self, event: &Event, reason: ExclusionReason) {
+        self.excluded.push(ExcludedEvent {
+            event: event.clone(),
+            reason,
+        });
+    }
+
+    /// Moves the reachable events `scope` now excludes, e.g. once their detail page gave
+    /// their time control, to the excluded ones.
+    pub fn recheck(&mut self, scope: &SearchScope) {
+        for reachable in std::mem::take(&mut self.reachable) {
+            match scope.exclusion(&reachable.event) {
+                Some(reason) => self.exclude(&reachable.event, reason),
+                None => self.reachable.push(reachable),
+            }
+        }
+    }
+
+    /// Logs how many events were excluded for each reason.
+    pub fn log_exclusions(&self) {
+        let mut counts: Vec<(&'static str, usize)> = Vec::new();
+        for excluded in &self.excluded {
+            let label = match excluded.reason {
+                ExclusionReason::Cancelled => "cancelled",
+                ExclusionReason::RatingOutOfRange { .. } => "outside the Elo bracket",
+                ExclusionReason::OutOfScope => "out of scope",
+                ExclusionReason::OtherTimeControl { .. } => "other time control",
+                ExclusionReason::NotRoadReachable { .. } => "not reachable by road",
+                ExclusionReason::UnknownDepartment { .. } => "unknown department",
+                ExclusionReason::TooFar { .. } => "too far",
+                ExclusionReason::RouteTooLong => "route too long",
+                ExclusionReason::RoutingFailed { .. } => "routing failed",
+                ExclusionReason::NotChecked => "not checked",
+            };
+            match counts.iter_mut().find(|(l, _)| *l == label) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((label, 1)),
+            }
+        }
+        for (label, count) in counts {
+            log::info!("Excluded {} events: {}", count, label);
+        }
+    }
+}
+
+/// Sorts the events into reachable and excluded ones. Overseas events are only routed
+/// from their own department, and trips to or from Corsica include a ferry crossing.
 pub fn filter_reachable_events(
-    origin_city: &str,
-    origin_query: &str,
+    origin: &ResolvedOrigin,
     events: &[Event],
     lookup: &DepartmentLookup,
     provider: &dyn RoutingProvider,
-    cache: &mut GeoCache,
-    max_hours: f64,
-) -> Vec<Event> {
-    let mut reachable = Vec::new();
+    rules: &ReachabilityRules,
+) -> SearchResults {
+    let origin_city = origin.label.as_str();
+    let origin_query = &origin.query;
+    let origin_territory = origin
+        .department
+        .as_deref()
+        .map_or(Territory::Mainland, Territory::of);
+    let mut results = SearchResults::default();
     log::info!(
         "Filtering {} events for reachability from '{}' (max {:.2} hours)...",
         events.len(),
         origin_city,
-        max_hours
+        rules.max_hours
     );
 
-    for event in events {
+    let mut events = events.iter();
+    for event in events.by_ref() {
+        if let Some(reason) = rules.scope.exclusion(event) {
+            results.exclude(event, reason);
+            continue;
+        }
+
         if origin_city
             .trim()
-            .eq_ignore_ascii_case(&event.location.trim())
+            .eq_ignore_ascii_case(event.location.trim())
         {
             log::info!("[REACHABLE - SAME TOWN] {}", event.title);
-            reachable.push(event.clone());
+            results.reachable.push(ReachableEvent {
+                event: event.clone(),
+                route: None,
+                ferry: None,
+            });
             continue;
         }
 
-        if let Some(department_name) = lookup.get_name(&event.department) {
-            let destination = format!("{}, {}", event.location, department_name);
-
-            match get_road_distance(origin_query, &destination, provider, cache) {
-                Ok(summary) if summary.duration_hours <= max_hours => {
-                    log::info!(
-                        "[REACHABLE] {} at {} ({:.1} km, {:.2} hrs)",
-                        event.title,
-                        event.location,
-                        summary.distance_km,
-                        summary.duration_hours
-                    );
-                    reachable.push(event.clone());
-                }
-                Ok(summary) => {
-                    log::trace!(
-                        "[TOO FAR] {} at {} ({:.2} hrs)",
-                        event.title,
-                        event.location,
-                        summary.duration_hours
-                    );
-                }
-                // THIS IS THE NEW, SMARTER ERROR HANDLING BLOCK
-                Err(e) => {
-                    // Try to downcast the error to our specific RoutingError type
-                    if let Some(routing_error) = e.downcast_ref::<RoutingError>() {
-                        match routing_error {
-                            // If the error is an API error with code 2004...
-                            RoutingError::ApiError { code: 2004, .. } => {
-                                // ...log it as a DEBUG message and move on. This is not a failure.
-                                log::debug!(
-                                    "[EXPECTED LIMIT] Route for {} is too long to calculate: {}",
-                                    event.title,
-                                    routing_error
-                                );
-                            }
-                            // For all other specific routing errors...
-                            _ => {
-                                // ...log them as critical errors.
-                                log::error!(
-                                    "Routing error for '{}' to '{}': {}",
-                                    origin_query,
-                                    destination,
-                                    e
-                                );
-                            }
-                        }
-                    } else {
-                        // For any other kind of error (not a RoutingError)...
-                        log::error!(
-                            "Unexpected error for '{}' to '{}': {}",
-                            origin_query,
-                            destination,
-                            e
-                        );
-                    }
-                }
+        let territory = Territory::of(&event.department);
+        let same_department = origin.department.as_deref() == Some(event.department.as_str());
+        if (territory == Territory::Overseas || origin_territory == Territory::Overseas)
+            && !same_department
+        {
+            log::debug!(
+                "[NOT ROAD REACHABLE] {} in {}",
+                event.title,
+                event.department
+            );
+            results.exclude(event, ExclusionReason::NotRoadReachable { territory });
+            continue;
+        }
+
+        let Some(destination) = lookup.build_geocode_query(&event.location, &event.department)
+        else {
+            log::warn!(
+                "[UNKNOWN DEPARTMENT] {} in '{}'",
+                event.title,
+                event.department
+            );
+            results.exclude(
+                event,
+                ExclusionReason::UnknownDepartment {
+                    department: event.department.clone(),
+                },
+            );
+            continue;
+        };
+        let crosses_sea =
+            (territory == Territory::Corsica) != (origin_territory == Territory::Corsica);
+        let travel = if crosses_sea {
+            fastest_ferry_trip(
+                origin_query,
+                &destination,
+                territory == Territory::Corsica,
+                rules.ferries,
+                lookup,
+                provider,
+            )
+            .map(|trip| (trip.total, Some(trip.ferry.name())))
+        } else {
+            get_road_distance(origin_query, &destination, provider).map(|summary| (summary, None))
+        };
+
+        match travel {
+            Ok((summary, ferry)) if summary.duration_hours <= rules.max_hours => {
+                log::info!(
+                    "[REACHABLE] {} at {} ({:.1} km, {:.2} hrs{})",
+                    event.title,
+                    event.location,
+                    summary.distance_km,
+                    summary.duration_hours,
+                    ferry
+                        .as_deref()
+                        .map(|f| format!(", ferry {}", f))
+                        .unwrap_or_default()
+                );
+                results.reachable.push(ReachableEvent {
+                    event: event.clone(),
+                    route: Some(summary),
+                    ferry,
+                });
+            }
+            Ok((summary, _)) => {
+                log::trace!(
+                    "[TOO FAR] {} at {} ({:.2} hrs)",
+                    event.title,
+                    event.location,
+                    summary.duration_hours
+                );
+                results.exclude(
+                    event,
+                    ExclusionReason::TooFar {
+                        duration_hours: summary.duration_hours,
+                    },
+                );
+            }
+            // "Route too long" is an expected limit, not a failure.
+            Err(RoutingError::RouteTooLong { message }) => {
+                log::debug!(
+                    "[EXPECTED LIMIT] Route for {} is too long to calculate: {}",
+                    event.title,
+                    message
+                );
+                results.exclude(event, ExclusionReason::RouteTooLong);
+            }
+            // No call can succeed until the quota resets: keep what we have.
+            Err(e) if e.is_quota_exhausted() => {
+                log::warn!("Stopping early, results are partial: {}", e);
+                results.exclude(event, ExclusionReason::NotChecked);
+                break;
+            }
+            Err(e) => {
+                log::error!(
+                    "Routing error for '{}' to '{}': {}",
+                    origin_query,
+                    destination,
+                    e
+                );
+                results.exclude(
+                    event,
+                    ExclusionReason::RoutingFailed {
+                        message: e.to_string(),
+                    },
+                );
             }
         }
     }
-    reachable
+    for event in events {
+        results.exclude(event, ExclusionReason::NotChecked);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ref_of(link: &str) -> Option<u64> {
+        EventId::from_link(link).map(|id| id.0)
+    }
+
+    #[test]
+    fn reads_the_ref_of_detail_links() {
+        assert_eq!(ref_of("FicheTournoi.aspx?Ref=65432"), Some(65432));
+        assert_eq!(
+            ref_of("https://www.echecs.asso.fr/FicheTournoi.aspx?Ref=65432"),
+            Some(65432)
+        );
+        // Any position and case of the parameter.
+        assert_eq!(ref_of("FicheTournoi.aspx?Lang=fr&ref=65432"), Some(65432));
+        assert_eq!(ref_of("FicheTournoi.aspx?Ref=65432&Lang=fr"), Some(65432));
+    }
+
+    #[test]
+    fn links_without_a_numeric_ref_have_no_id() {
+        assert_eq!(ref_of("FicheTournoi.aspx?Ref="), None);
+        assert_eq!(ref_of("FicheTournoi.aspx?Ref=abc"), None);
+        assert_eq!(ref_of("FicheTournoi.aspx?Id=65432"), None);
+        assert_eq!(ref_of("FicheTournoi.aspx"), None);
+        assert_eq!(ref_of(""), None);
+    }
+
+    #[test]
+    fn parses_a_number_or_a_link() {
+        assert_eq!(" 65432 ".parse::<EventId>(), Ok(EventId(65432)));
+        assert_eq!(
+            "FicheTournoi.aspx?Ref=65432".parse::<EventId>(),
+            Ok(EventId(65432))
+        );
+        assert!("open".parse::<EventId>().is_err());
+    }
+
+    #[test]
+    fn detail_url_uses_the_site_root() {
+        assert_eq!(
+            EventId(65432).detail_url(),
+            "https://www.echecs.asso.fr/FicheTournoi.aspx?Ref=65432"
+        );
+    }
+
+    #[test]
+    fn results_url_names_the_page() {
+        assert_eq!(
+            EventId(65432).results_url("Ls"),
+            "https://www.echecs.asso.fr/Resultats.aspx?URL=Tournois/Id/65432/65432&Action=Ls"
+        );
+    }
 }
@@ -0,0 +1,131 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::departments::normalize_name;
+use super::events::{Event, ExcludedEvent, ReachableEvent, SearchResults};
+use super::time_control::TimeControl;
+
+/// Tournaments held together: same town, overlapping dates and similar titles, e.g.
+/// "Championnat Bretagne TC 2025" with its "... Rapide" and "... Blitz".
+#[derive(Debug, Clone, Serialize)]
+pub struct Festival<T> {
+    /// The shortest member title, usually the main tournament's.
+    pub name: String,
+    pub department: String,
+    pub location: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// The members' time control if they share one, else mixed.
+    pub time_control: TimeControl,
+    pub events: Vec<T>,
+}
+
+impl<T> Festival<T> {
+    fn new(item: T, event: impl Fn(&T) -> &Event) -> Self {
+        let e = event(&item);
+        Self {
+            name: e.title.clone(),
+            department: e.department.clone(),
+            location: e.location.clone(),
+            start_date: e.start_date,
+            end_date: e.end_date,
+            time_control: e.time_control.clone(),
+            events: vec![item],
+        }
+    }
+
+    fn add(&mut self, item: T, event: impl Fn(&T) -> &Event) {
+        let event = event(&item);
+        if event.title.len() < self.name.len() {
+            self.name = event.title.clone();
+        }
+        self.start_date = self.start_date.min(event.start_date);
+        self.end_date = self.end_date.max(event.end_date);
+        self.time_control =
+            TimeControl::combine(vec![self.time_control.clone(), event.time_control.clone()]);
+        self.events.push(item);
+    }
+}
+
+/// The words that tell tournaments apart, without time-control words and years.
+fn title_words(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(normalize_name)
+        .filter(|w| !w.is_empty() && !TimeControl::is_keyword(w))
+        .filter(|w| !(w.len() == 4 && w.chars().all(|c| c.is_ascii_digit())))
+        .collect()
+}
+
+/// Titles are similar when they share a word and one's words contain the other's, or
+/// they share at least half of their words. A title of time-control words only ("Blitz")
+/// has no words to share, so it is similar to none.
+fn similar_titles(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    let shared = a.intersection(b).count();
+    if shared == 0 {
+        return false;
+    }
+    a.is_subset(b) || b.is_subset(a) || shared * 2 >= a.union(b).count()
+}
+
+/// Groups `items` into festivals, by the event each one is about. Events alone in their
+/// town keep a festival of their own, so every event appears exactly once.
+pub fn group_festivals<T>(items: Vec<T>, event: impl Fn(&T) -> &Event) -> Vec<Festival<T>> {
+    let mut festivals: Vec<Festival<T>> = Vec::new();
+    // Title words of every member, kept alongside each festival.
+    let mut festival_words: Vec<Vec<HashSet<String>>> = Vec::new();
+
+    let mut items = items;
+    items.sort_by(|a, b| {
+        let (a, b) = (event(a), event(b));
+        (a.start_date, &a.title).cmp(&(b.start_date, &b.title))
+    });
+    for item in items {
+        let e = event(&item);
+        let words = title_words(&e.title);
+        let location = normalize_name(&e.location);
+        let found = festivals
+            .iter()
+            .zip(&festival_words)
+            .position(|(f, members)| {
+                f.department == e.department
+                    && normalize_name(&f.location) == location
+                    && f.start_date <= e.end_date
+                    && e.start_date <= f.end_date
+                    && members.iter().any(|m| similar_titles(m, &words))
+            });
+        match found {
+            Some(i) => {
+                festivals[i].add(item, &event);
+                festival_words[i].push(words);
+            }
+            None => {
+                festivals.push(Festival::new(item, &event));
+                festival_words.push(vec![words]);
+            }
+        }
+    }
+
+    let grouped = festivals.iter().filter(|f| f.events.len() > 1).count();
+    if grouped > 0 {
+        log::info!("Grouped events into {} multi-tournament festivals", grouped);
+    }
+    festivals
+}
+
+/// `SearchResults` with the reachable events grouped into festivals.
+#[derive(Debug, Clone, Serialize)]
+pub struct FestivalResults {
+    pub festivals: Vec<Festival<ReachableEvent>>,
+    pub excluded: Vec<ExcludedEvent>,
+}
+
+impl From<SearchResults> for FestivalResults {
+    fn from(results: SearchResults) -> Self {
+        Self {
+            festivals: group_festivals(results.reachable, |r| &r.event),
+            excluded: results.excluded,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use super::departments::DepartmentLookup;
+use super::eligibility::Eligibility;
+use super::error::ConfigError;
+use super::events::{Event, ExclusionReason};
+use super::status::EventStatus;
+use super::time_control::TimeControl;
+
+/// Geographic limits of a search, set in `[filters]` or on the command line.
+/// They are applied to the scraped events before any routing.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    /// Only these departments (by number), together with those of `regions`.
+    pub departments: Vec<String>,
+    /// Only the departments of these regions, e.g. "Bretagne".
+    pub regions: Vec<String>,
+    /// Never these departments, e.g. `["75", "92", "93", "94"]`.
+    pub exclude_departments: Vec<String>,
+    /// Only departments at most this many borders away from the origin's (0 = same department).
+    pub max_hops: Option<u32>,
+    /// Keep every event in scope without routing, whatever the drive time.
+    pub ignore_travel_time: bool,
+    /// Only events of these kinds; events whose time control is unknown are kept.
+    pub time_controls: Vec<TimeControl>,
+    /// Keep events marked as cancelled, which are left out by default.
+    pub include_cancelled: bool,
+    /// Leave out events whose Elo bracket `[search] player` is outside of.
+    pub eligible_only: bool,
+}
+
+impl SearchFilters {
+    /// Resolves the filters into the set of departments a search covers.
+    /// `origin_department` is needed for `max_hops`.
+    pub fn scope(
+        &self,
+        lookup: &DepartmentLookup,
+        origin_department: Option<&str>,
+    ) -> Result<SearchScope, ConfigError> {
+        let invalid = |name: &str, value: &str| ConfigError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        for code in self.departments.iter().chain(&self.exclude_departments) {
+            if !lookup.is_valid_department(code) {
+                return Err(invalid("department filter", code));
+            }
+        }
+
+        let mut allowed: Option<HashSet<String>> = None;
+        if !self.departments.is_empty() || !self.regions.is_empty() {
+            let mut listed: HashSet<String> = self.departments.iter().cloned().collect();
+            for region in &self.regions {
+                let departments = lookup.in_region(region);
+                if departments.is_empty() {
+                    return Err(invalid("region filter", region));
+                }
+                listed.extend(departments.into_iter().map(|d| d.code.clone()));
+            }
+            allowed = Some(listed);
+        }
+        if let Some(max_hops) = self.max_hops {
+            let origin = origin_department
+                .ok_or_else(|| invalid("max_hops", "the origin's department is unknown"))?;
+            let nearby: HashSet<String> = lookup
+                .within_hops(origin, max_hops)
+                .into_iter()
+                .map(|(d, _)| d.code.clone())
+                .collect();
+            allowed = Some(match allowed {
+                Some(listed) => listed.intersection(&nearby).cloned().collect(),
+                None => nearby,
+            });
+        }
+
+        let excluded: HashSet<String> = self.exclude_departments.iter().cloned().collect();
+        if let Some(allowed) = &allowed {
+            let mut codes: Vec<&String> = allowed.difference(&excluded).collect();
+            codes.sort();
+            log::info!(
+                "Search limited to {} departments: {}",
+                codes.len(),
+                codes
+                    .iter()
+                    .map(|c| c.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        } else if !excluded.is_empty() {
+            log::info!(
+                "Search excludes departments: {}",
+                self.exclude_departments.join(", ")
+            );
+        }
+        Ok(SearchScope {
+            allowed,
+            excluded,
+            time_controls: self.time_controls.clone(),
+            include_cancelled: self.include_cancelled,
+            eligible_only: self.eligible_only,
+        })
+    }
+}
+
+/// The departments and kinds of events a search covers, resolved from `SearchFilters`.
+/// Cancelled events are outside it unless `include_cancelled` is set.
+#[derive(Debug, Clone, Default)]
+pub struct SearchScope {
+    /// `None` when every department is allowed.
+    allowed: Option<HashSet<String>>,
+    excluded: HashSet<String>,
+    time_controls: Vec<TimeControl>,
+    include_cancelled: bool,
+    eligible_only: bool,
+}
+
+impl SearchScope {
+    pub fn contains(&self, department: &str) -> bool {
+        !self.excluded.contains(department)
+            && self
+                .allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(department))
+    }
+
+    /// Why `event` is outside the scope, if it is.
+    pub fn exclusion(&self, event: &Event) -> Option<ExclusionReason> {
+        if event.status == EventStatus::Cancelled && !self.include_cancelled {
+            Some(ExclusionReason::Cancelled)
+        } else if let Some(Eligibility::Ineligible { elo }) = event.eligibility
+            && self.eligible_only
+        {
+            Some(ExclusionReason::RatingOutOfRange {
+                elo,
+                constraint: event.rating_constraint.unwrap_or_default(),
+            })
+        } else if !self.contains(&event.department) {
+            Some(ExclusionReason::OutOfScope)
+        } else if !event.time_control.matches(&self.time_controls) {
+            Some(ExclusionReason::OtherTimeControl {
+                time_control: event.time_control.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The events inside the scope.
+    pub fn filter(&self, events: &[Event]) -> Vec<Event> {
+        let kept: Vec<Event> = events
+            .iter()
+            .filter(|e| self.exclusion(e).is_none())
+            .cloned()
+            .collect();
+        if kept.len() < events.len() {
+            log::info!(
+                "{} of {} events are outside the search scope",
+                events.len() - kept.len(),
+                events.len()
+            );
+        }
+        kept
+    }
+}
@@ -1,5 +1,20 @@
+pub mod carpool;
+pub mod client;
 pub mod config;
+pub mod daytrip;
 pub mod departments;
+pub mod details;
+pub mod eligibility;
+pub mod error;
 pub mod events;
+pub mod festival;
+pub mod filters;
+pub mod planner;
+pub mod players;
+pub mod results;
 pub mod routing;
+pub mod status;
+pub mod sync;
+pub mod territory;
+pub mod time_control;
 pub mod util;
@@ -0,0 +1,298 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::departments::DepartmentLookup;
+use super::events::Event;
+use super::routing::{
+    geocode::GeocodeQuery,
+    route::{get_road_distance, log_routing_failure},
+    service::RoutingProvider,
+};
+
+/// What the planner optimises, once the constraints are met.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourObjective {
+    /// Play as many events as allowed, with the least total driving.
+    MinTravel,
+    /// Play as many rated games as possible, breaking ties on total driving.
+    MaxGames,
+}
+
+impl std::str::FromStr for TourObjective {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "travel" | "min-travel" => Ok(TourObjective::MinTravel),
+            "games" | "max-games" => Ok(TourObjective::MaxGames),
+            other => Err(format!("Unknown tour objective: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TourConstraints {
+    /// Only events fully inside `[start, end]` are considered.
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub max_events: usize,
+    /// Longest single drive allowed, whether from home or between two events.
+    pub max_hours_per_trip: f64,
+    /// Free days required between the end of one event and the start of the next.
+    pub min_rest_days: i64,
+    /// Events starting at most this many days after the previous one ends may be
+    /// chained by driving directly between them instead of going home in between.
+    pub chain_max_gap_days: i64,
+    pub objective: TourObjective,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TourStop {
+    pub event: Event,
+    /// True when reached directly from the previous event rather than from home.
+    pub chained: bool,
+    /// Driving to reach this event (including the previous return home, if any).
+    pub travel_hours: f64,
+    pub estimated_games: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct TourPlan {
+    pub stops: Vec<TourStop>,
+    /// Drive home after the last event.
+    pub return_hours: f64,
+    pub total_travel_hours: f64,
+    pub total_games: u32,
+}
+
+/// Rough number of rated games for an event, from its length in days. Never decreases
+/// with the length, so a longer event is never worth fewer games to the planner.
+pub fn estimate_rated_games(event: &Event) -> u32 {
+    match (event.end_date - event.start_date).num_days() + 1 {
+        ..=2 => 5,
+        3 => 7,
+        _ => 9,
+    }
+}
+
+/// Picks a schedule of non-conflicting events around `home_query` within the constraints.
+pub fn plan_tour(
+    home_query: &GeocodeQuery,
+    events: &[Event],
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+    constraints: &TourConstraints,
+) -> TourPlan {
+    // 1. Candidates: in range, routable from home within the per-trip limit.
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for event in events {
+        if event.start_date < constraints.start || event.end_date > constraints.end {
+            continue;
+        }
+        let Some(destination) = lookup.build_geocode_query(&event.location, &event.department)
+        else {
+            continue;
+        };
+        match get_road_distance(home_query, &destination, provider) {
+            Ok(summary) if summary.duration_hours <= constraints.max_hours_per_trip => {
+                candidates.push(Candidate {
+                    event: event.clone(),
+                    destination,
+                    home_hours: summary.duration_hours,
+                    games: estimate_rated_games(event),
+                });
+            }
+            Ok(_) => log::trace!("[TOO FAR] {} excluded from tour", event.title),
+            Err(e) => {
+                log_routing_failure(home_query, &destination, &e);
+                if e.is_quota_exhausted() {
+                    break;
+                }
+            }
+        }
+    }
+    candidates.sort_by_key(|c| (c.event.start_date, c.event.end_date));
+    log::info!(
+        "Planning a tour over {} candidate events ({} to {})...",
+        candidates.len(),
+        constraints.start,
+        constraints.end
+    );
+
+    // 2. Direct drives between chainable events.
+    let mut chain_hours = HashMap::new();
+    'chains: for (j, prev) in candidates.iter().enumerate() {
+        for (i, next) in candidates.iter().enumerate().skip(j + 1) {
+            if !compatible(prev, next, constraints) || !chainable(prev, next, constraints) {
+                continue;
+            }
+            match get_road_distance(&prev.destination, &next.destination, provider) {
+                Ok(summary) if summary.duration_hours <= constraints.max_hours_per_trip => {
+                    chain_hours.insert((j, i), summary.duration_hours);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    log_routing_failure(&prev.destination, &next.destination, &e);
+                    if e.is_quota_exhausted() {
+                        break 'chains;
+                    }
+                }
+            }
+        }
+    }
+
+    optimise(&candidates, &chain_hours, constraints)
+}
+
+struct Candidate {
+    event: Event,
+    destination: GeocodeQuery,
+    home_hours: f64,
+    games: u32,
+}
+
+/// Best partial schedule ending at a given event with a given number of stops.
+#[derive(Clone, Copy)]
+struct State {
+    games: u32,
+    /// Travel so far, excluding the final return home.
+    travel: f64,
+    prev: Option<usize>,
+    chained: bool,
+}
+
+fn compatible(prev: &Candidate, next: &Candidate, constraints: &TourConstraints) -> bool {
+    let free_days = (next.event.start_date - prev.event.end_date).num_days() - 1;
+    next.event.start_date > prev.event.end_date && free_days >= constraints.min_rest_days
+}
+
+fn chainable(prev: &Candidate, next: &Candidate, constraints: &TourConstraints) -> bool {
+    (next.event.start_date - prev.event.end_date).num_days() <= constraints.chain_max_gap_days
+}
+
+/// Is `a` a better partial schedule than `b` for the objective? Travel always breaks ties.
+fn better(a: &State, b: &State, objective: TourObjective) -> bool {
+    match objective {
+        TourObjective::MaxGames if a.games != b.games => a.games > b.games,
+        _ => a.travel < b.travel,
+    }
+}
+
+/// Dynamic programme over (last event, number of events), events sorted by date.
+fn optimise(
+    candidates: &[Candidate],
+    chain_hours: &HashMap<(usize, usize), f64>,
+    constraints: &TourConstraints,
+) -> TourPlan {
+    let n = candidates.len();
+    let k_max = constraints.max_events.min(n);
+    if k_max == 0 {
+        return TourPlan::default();
+    }
+    // best[i][k - 1]: best schedule of k events ending at candidate i.
+    let mut best: Vec<Vec<Option<State>>> = vec![vec![None; k_max]; n];
+
+    for i in 0..n {
+        best[i][0] = Some(State {
+            games: candidates[i].games,
+            travel: candidates[i].home_hours,
+            prev: None,
+            chained: false,
+        });
+        for j in 0..i {
+            if !compatible(&candidates[j], &candidates[i], constraints) {
+                continue;
+            }
+            // Going home in between, or driving directly when the events chain.
+            let via_home = candidates[j].home_hours + candidates[i].home_hours;
+            let (leg, chained) = match chain_hours.get(&(j, i)) {
+                Some(&direct) if direct < via_home => (direct, true),
+                _ => (via_home, false),
+            };
+            for k in 1..k_max {
+                let Some(from) = best[j][k - 1] else {
+                    continue;
+                };
+                let state = State {
+                    games: from.games + candidates[i].games,
+                    travel: from.travel + leg,
+                    prev: Some(j),
+                    chained,
+                };
+                if best[i][k].is_none_or(|current| better(&state, &current, constraints.objective))
+                {
+                    best[i][k] = Some(state);
+                }
+            }
+        }
+    }
+
+    // Close every schedule with the drive home and keep the best one.
+    let mut winner: Option<(usize, usize, State)> = None;
+    for (i, row) in best.iter().enumerate() {
+        for (k, state) in row.iter().enumerate() {
+            let Some(state) = state else { continue };
+            let closed = State {
+                travel: state.travel + candidates[i].home_hours,
+                ..*state
+            };
+            let replace = match &winner {
+                None => true,
+                Some((_, best_k, current)) => match constraints.objective {
+                    TourObjective::MinTravel if k != *best_k => k > *best_k,
+                    objective => better(&closed, current, objective),
+                },
+            };
+            if replace {
+                winner = Some((i, k, closed));
+            }
+        }
+    }
+
+    let Some((last, k, total)) = winner else {
+        return TourPlan::default();
+    };
+
+    // Walk the predecessors back to rebuild the schedule.
+    let mut stops = Vec::with_capacity(k + 1);
+    let (mut i, mut k) = (last, k);
+    loop {
+        let state = best[i][k].expect("reconstructed states exist");
+        let travel_hours = match state.prev {
+            Some(j) => best_travel(&best, i, k) - best_travel(&best, j, k - 1),
+            None => state.travel,
+        };
+        stops.push(TourStop {
+            event: candidates[i].event.clone(),
+            chained: state.chained,
+            travel_hours,
+            estimated_games: candidates[i].games,
+        });
+        match state.prev {
+            Some(j) => {
+                i = j;
+                k -= 1;
+            }
+            None => break,
+        }
+    }
+    stops.reverse();
+
+    let return_hours = candidates[last].home_hours;
+    log::info!(
+        "Planned {} events: {:.2} hours of driving, ~{} rated games",
+        stops.len(),
+        total.travel,
+        total.games
+    );
+    TourPlan {
+        stops,
+        return_hours,
+        total_travel_hours: total.travel,
+        total_games: total.games,
+    }
+}
+
+fn best_travel(best: &[Vec<Option<State>>], i: usize, k: usize) -> f64 {
+    best[i][k].map(|s| s.travel).unwrap_or_default()
+}
@@ -0,0 +1,351 @@
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+use super::client::FfeClient;
+use super::details::{collect_fields, field};
+use super::error::ScrapeError;
+use super::events::{BASE_URL, Event, EventId};
+use super::time_control::TimeControl;
+
+/// How a rating was obtained, from the letter after it on FFE lists ("1850 F").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingType {
+    /// "F": FIDE rating.
+    Fide,
+    /// "N": national (FFE) rating.
+    National,
+    /// "E": estimated, for players without a rating yet.
+    Estimated,
+}
+
+impl RatingType {
+    fn from_letter(letter: &str) -> Option<Self> {
+        match letter.trim().to_ascii_uppercase().as_str() {
+            "F" => Some(RatingType::Fide),
+            "N" => Some(RatingType::National),
+            "E" => Some(RatingType::Estimated),
+            _ => None,
+        }
+    }
+}
+
+/// An Elo with its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub elo: u32,
+    /// `None` when the list shows the number alone.
+    pub kind: Option<RatingType>,
+}
+
+impl Rating {
+    /// Parses "1850 F", "1850F", "1199 E (estimé)" or "1850".
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let elo = text[..digits].parse().ok().filter(|elo| *elo > 0)?;
+        let letter = text[digits..].trim_start().chars().next();
+        Some(Self {
+            elo,
+            kind: letter.and_then(|l| RatingType::from_letter(&l.to_string())),
+        })
+    }
+}
+
+/// A player on an event's list of registered players.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    /// As listed, usually "SURNAME First name".
+    pub name: String,
+    /// From the link to the player's FFE page, when the list has one.
+    pub ffe_id: Option<String>,
+    /// FIDE or FFE title as abbreviated on the list, e.g. "g" or "mf".
+    pub title: Option<String>,
+    pub rating: Option<Rating>,
+    /// Age category and sex, e.g. "SenM" or "PupF".
+    pub category: Option<String>,
+    pub club: Option<String>,
+}
+
+/// Aggregate figures on the players registered for an event.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldStats {
+    pub count: usize,
+    /// Over the players with a rating, estimated ones included.
+    pub average_elo: Option<f64>,
+    pub top_elo: Option<u32>,
+    pub titled: usize,
+}
+
+impl FieldStats {
+    pub fn from_players(players: &[Player]) -> Self {
+        let elos: Vec<u32> = players
+            .iter()
+            .filter_map(|p| p.rating.map(|r| r.elo))
+            .collect();
+        Self {
+            count: players.len(),
+            average_elo: (!elos.is_empty())
+                .then(|| elos.iter().map(|e| f64::from(*e)).sum::<f64>() / elos.len() as f64),
+            top_elo: elos.iter().max().copied(),
+            titled: players.iter().filter(|p| p.title.is_some()).count(),
+        }
+    }
+}
+
+/// A cell's text, with runs of whitespace collapsed.
+pub(crate) fn cell_text(cell: &ElementRef) -> String {
+    cell.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads the player id from a `FicheJoueur.aspx?Id=...` link in the row, if any.
+fn player_id(row: &ElementRef) -> Option<String> {
+    let a_selector = Selector::parse("a[href]").unwrap();
+    row.select(&a_selector)
+        .filter_map(|a| a.value().attr("href"))
+        .find(|href| href.contains("FicheJoueur"))
+        .and_then(|href| href.split_once("Id=").map(|(_, id)| id))
+        .map(|id| id.split('&').next().unwrap_or(id).trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Where each field sits in the rows of a player list, read from its header row.
+#[derive(Clone, Copy)]
+struct Columns {
+    name: usize,
+    elo: usize,
+    title: Option<usize>,
+    category: Option<usize>,
+    club: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(labels: &[String]) -> Option<Self> {
+        let position = |names: &[&str]| labels.iter().position(|l| names.contains(&l.as_str()));
+        let name = position(&["nom"])?;
+        Some(Self {
+            name,
+            elo: position(&["elo"])?,
+            title: name.checked_sub(1).filter(|i| labels[*i].is_empty()),
+            category: position(&["cat.", "cat"]),
+            club: position(&["club"]),
+        })
+    }
+}
+
+/// Parses the list of registered players (`Resultats.aspx?...&Action=Ls`).
+///
+/// Columns are found from the header row ("Nom", "Elo", "Cat.", "Club"); the title
+/// sits in the unlabelled column before the name. An event nobody has registered for
+/// yet gives an empty list.
+pub fn parse_player_list_html(html: &str) -> Vec<Player> {
+    let document = Html::parse_document(html);
+    let row_selector = Selector::parse("tr").unwrap();
+    let td_selector = Selector::parse("td, th").unwrap();
+    let mut columns: Option<Columns> = None;
+    let mut players = Vec::new();
+
+    for row in document.select(&row_selector) {
+        let cells: Vec<String> = row.select(&td_selector).map(|c| cell_text(&c)).collect();
+        let labels: Vec<String> = cells.iter().map(|c| c.to_lowercase()).collect();
+        if let Some(header) = Columns::from_header(&labels) {
+            columns = Some(header);
+            continue;
+        }
+        let Some(columns) = columns else {
+            continue;
+        };
+        let Some(name) = cells.get(columns.name).filter(|n| !n.is_empty()) else {
+            continue;
+        };
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|i| cells.get(i))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+        players.push(Player {
+            name: name.clone(),
+            ffe_id: player_id(&row),
+            title: value(columns.title),
+            rating: value(Some(columns.elo)).and_then(|e| Rating::parse(&e)),
+            category: value(columns.category),
+            club: value(columns.club),
+        });
+    }
+    players
+}
+
+/// Fetches the players registered for the event with this id.
+pub fn fetch_registered_players(
+    client: &FfeClient,
+    id: EventId,
+) -> Result<Vec<Player>, ScrapeError> {
+    let url = id.results_url("Ls");
+    log::debug!("Fetching registered players from {}", url);
+    let html = client.fetch_page(&url)?;
+    Ok(parse_player_list_html(&html))
+}
+
+/// Fetches the list of registered players of each event and attaches its statistics.
+pub fn attach_field_stats<'a>(events: impl IntoIterator<Item = &'a mut Event>, client: &FfeClient) {
+    for event in events {
+        match fetch_registered_players(client, event.id) {
+            Ok(players) => {
+                let stats = FieldStats::from_players(&players);
+                log::debug!(
+                    "{}: {} registered, average Elo {:?}",
+                    event.title,
+                    stats.count,
+                    stats.average_elo
+                );
+                event.registered = Some(stats);
+            }
+            Err(e) => log::warn!("No player list for {}: {}", event.title, e),
+        }
+    }
+}
+
+/// A player's FFE page (`FicheJoueur.aspx?Id=...`): current ratings for each kind of game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    /// The `Id` of the page, as in `Player::ffe_id`.
+    pub id: String,
+    pub name: Option<String>,
+    pub club: Option<String>,
+    pub standard: Option<Rating>,
+    pub rapid: Option<Rating>,
+    pub blitz: Option<Rating>,
+}
+
+impl PlayerProfile {
+    /// The Elo that counts for an event of this kind: standard for classical, mixed and
+    /// unknown events.
+    pub fn rating_for(&self, time_control: &TimeControl) -> Option<Rating> {
+        match time_control {
+            TimeControl::Rapid => self.rapid,
+            TimeControl::Blitz => self.blitz,
+            TimeControl::Classical | TimeControl::Mixed(_) | TimeControl::Unknown => self.standard,
+        }
+    }
+}
+
+pub fn player_url(id: &str) -> String {
+    format!("{}FicheJoueur.aspx?Id={}", BASE_URL, id)
+}
+
+/// Parses a player's FFE page.
+pub fn parse_player_profile_html(id: &str, html: &str) -> PlayerProfile {
+    let document = Html::parse_document(html);
+    let fields = collect_fields(&document);
+    let rating = |names: &[&str]| field(&fields, names).and_then(Rating::parse);
+    PlayerProfile {
+        id: id.to_string(),
+        name: field(&fields, &["nom"]).map(str::to_string),
+        club: field(&fields, &["club"]).map(str::to_string),
+        standard: rating(&["elo standard", "elo lent", "elo", "standard"]),
+        rapid: rating(&["elo rapide", "rapide"]),
+        blitz: rating(&["elo blitz", "blitz"]),
+    }
+}
+
+/// Fetches a player's current ratings from their FFE page.
+pub fn fetch_player_profile(client: &FfeClient, id: &str) -> Result<PlayerProfile, ScrapeError> {
+    let url = player_url(id);
+    log::debug!("Fetching player profile from {}", url);
+    let html = client.fetch_page(&url)?;
+    let profile = parse_player_profile_html(id, &html);
+    if profile.standard.is_none() && profile.rapid.is_none() && profile.blitz.is_none() {
+        return Err(ScrapeError::Layout {
+            url,
+            message: "no Elo found on the player page".to_string(),
+        });
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAYER_LIST: &str = "<table>\
+        <tr><td>Titre</td></tr>\
+        <tr><td>N°</td><td></td><td>Nom</td><td>Elo</td><td>Cat.</td><td>Club</td></tr>\
+        <tr><td>1</td><td>g</td>\
+            <td><a href=\"FicheJoueur.aspx?Id=12345&Lang=fr\">DURAND Paul</a></td>\
+            <td>2450 F</td><td>SenM</td><td>Rennes Paul Bert</td></tr>\
+        <tr><td>2</td><td></td><td>MARTIN Anne</td><td>1199 E</td><td>PupF</td><td></td></tr>\
+        <tr><td>3</td><td></td><td></td><td>1500 F</td><td></td><td></td></tr>\
+        </table>";
+
+    fn rating(elo: u32, kind: Option<RatingType>) -> Option<Rating> {
+        Some(Rating { elo, kind })
+    }
+
+    #[test]
+    fn parses_ratings_with_or_without_their_type() {
+        assert_eq!(
+            Rating::parse("1850 F"),
+            rating(1850, Some(RatingType::Fide))
+        );
+        assert_eq!(Rating::parse("1850F"), rating(1850, Some(RatingType::Fide)));
+        assert_eq!(
+            Rating::parse("1620 N"),
+            rating(1620, Some(RatingType::National))
+        );
+        assert_eq!(
+            Rating::parse("1199 E (estimé)"),
+            rating(1199, Some(RatingType::Estimated))
+        );
+        assert_eq!(Rating::parse("1850"), rating(1850, None));
+        assert_eq!(Rating::parse("0"), None);
+        assert_eq!(Rating::parse("E"), None);
+        assert_eq!(Rating::parse(""), None);
+    }
+
+    #[test]
+    fn parses_the_registered_players() {
+        let players = parse_player_list_html(PLAYER_LIST);
+        assert_eq!(players.len(), 2, "the row without a name is skipped");
+
+        let durand = &players[0];
+        assert_eq!(durand.name, "DURAND Paul");
+        assert_eq!(durand.ffe_id.as_deref(), Some("12345"));
+        assert_eq!(durand.title.as_deref(), Some("g"));
+        assert_eq!(durand.rating, rating(2450, Some(RatingType::Fide)));
+        assert_eq!(durand.category.as_deref(), Some("SenM"));
+        assert_eq!(durand.club.as_deref(), Some("Rennes Paul Bert"));
+
+        let martin = &players[1];
+        assert_eq!(martin.name, "MARTIN Anne");
+        assert_eq!(martin.ffe_id, None);
+        assert_eq!(martin.title, None);
+        assert_eq!(martin.club, None);
+    }
+
+    #[test]
+    fn summarises_the_field() {
+        let stats = FieldStats::from_players(&parse_player_list_html(PLAYER_LIST));
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.top_elo, Some(2450));
+        assert_eq!(stats.average_elo, Some((2450.0 + 1199.0) / 2.0));
+        assert_eq!(stats.titled, 1);
+    }
+
+    #[test]
+    fn needs_a_header_row_with_names() {
+        assert!(parse_player_list_html("").is_empty());
+        assert!(
+            parse_player_list_html("<table><tr><td>1</td><td>DURAND Paul</td></tr></table>")
+                .is_empty()
+        );
+        assert!(
+            parse_player_list_html("<table><tr><td>Nom</td><td>Elo</td></tr></table>").is_empty()
+        );
+    }
+}
@@ -0,0 +1,701 @@
+use csv::Writer;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use super::client::FfeClient;
+use super::departments::normalize_name;
+use super::error::{ExportError, ScrapeError};
+use super::events::EventId;
+use super::players::{Rating, RatingType, cell_text};
+
+/// How a player's game of one round ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+    /// Won because the opponent didn't show up (">").
+    ForfeitWin,
+    /// Lost by not showing up ("<").
+    ForfeitLoss,
+    /// No opponent this round ("EXE").
+    Bye,
+    NotPlayed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Color {
+    White,
+    Black,
+}
+
+/// One cell of a crosstable, e.g. "+ 12B": a win against number 12, with White.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RoundResult {
+    pub outcome: Outcome,
+    /// The opponent's rank in the same table.
+    pub opponent: Option<u32>,
+    pub color: Option<Color>,
+    /// What a bye was worth: from the cell's sign ("+EXE", "=EXE", "-EXE"), or for a bare
+    /// "EXE", from the rest of the player's total.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bye_points: Option<f64>,
+}
+
+impl RoundResult {
+    pub fn parse(cell: &str) -> Self {
+        let cell: String = cell
+            .trim()
+            .to_uppercase()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let not_played = Self {
+            outcome: Outcome::NotPlayed,
+            opponent: None,
+            color: None,
+            bye_points: None,
+        };
+        let mut chars = cell.chars();
+        let sign = chars.next();
+        if cell.ends_with("EXE") {
+            let bye_points = match sign {
+                Some('+') => Some(1.0),
+                Some('=') => Some(0.5),
+                Some('-') => Some(0.0),
+                _ => None,
+            };
+            return Self {
+                outcome: Outcome::Bye,
+                bye_points,
+                ..not_played
+            };
+        }
+        let outcome = match sign {
+            Some('+') => Outcome::Win,
+            Some('=') => Outcome::Draw,
+            Some('-') => Outcome::Loss,
+            Some('>') => Outcome::ForfeitWin,
+            Some('<') => Outcome::ForfeitLoss,
+            _ => return not_played,
+        };
+        let rest: String = chars.collect();
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let color = match &rest[digits..] {
+            "B" | "W" => Some(Color::White),
+            "N" => Some(Color::Black),
+            _ => None,
+        };
+        Self {
+            outcome,
+            opponent: rest[..digits].parse().ok(),
+            color,
+            bye_points: None,
+        }
+    }
+
+    /// Points scored this round; `None` for a bye whose value is unknown.
+    pub fn points(&self) -> Option<f64> {
+        match self.outcome {
+            Outcome::Win | Outcome::ForfeitWin => Some(1.0),
+            Outcome::Draw => Some(0.5),
+            Outcome::Bye => self.bye_points,
+            Outcome::Loss | Outcome::ForfeitLoss | Outcome::NotPlayed => Some(0.0),
+        }
+    }
+}
+
+/// Gives a bare "EXE" the points of the row's total the other rounds don't account for,
+/// when there is a single such bye and the rest is a valid game score.
+fn settle_bye(rounds: &mut [RoundResult], total: f64) {
+    let mut unknown = (0..rounds.len()).filter(|&i| rounds[i].points().is_none());
+    let (Some(bye), None) = (unknown.next(), unknown.next()) else {
+        return;
+    };
+    let rest = total - rounds.iter().filter_map(RoundResult::points).sum::<f64>();
+    if [0.0, 0.5, 1.0].contains(&rest) {
+        rounds[bye].bye_points = Some(rest);
+    }
+}
+
+/// Written back in the FFE notation, e.g. "+12B".
+impl fmt::Display for RoundResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.outcome {
+            Outcome::Win => "+",
+            Outcome::Draw => "=",
+            Outcome::Loss => "-",
+            Outcome::ForfeitWin => ">",
+            Outcome::ForfeitLoss => "<",
+            Outcome::Bye => {
+                let sign = match self.bye_points {
+                    Some(p) if p >= 1.0 => "+",
+                    Some(p) if p > 0.0 => "=",
+                    Some(_) => "-",
+                    None => "",
+                };
+                return write!(f, "{}EXE", sign);
+            }
+            Outcome::NotPlayed => return Ok(()),
+        };
+        f.write_str(sign)?;
+        if let Some(opponent) = self.opponent {
+            write!(f, "{}", opponent)?;
+        }
+        match self.color {
+            Some(Color::White) => f.write_str("B"),
+            Some(Color::Black) => f.write_str("N"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A tie-break score, named as in the table header (e.g. "Tr.", "Bu."); `None` when the
+/// player's cell is empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tiebreak {
+    pub name: String,
+    pub value: Option<f64>,
+}
+
+/// A row of an event's final standings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    /// Shared by players tied on every tie-break.
+    pub rank: u32,
+    pub name: String,
+    pub title: Option<String>,
+    pub rating: Option<Rating>,
+    pub category: Option<String>,
+    pub federation: Option<String>,
+    pub league: Option<String>,
+    pub club: Option<String>,
+    pub points: f64,
+    /// One per tie-break column of the table, in its order.
+    pub tiebreaks: Vec<Tiebreak>,
+    pub performance: Option<u32>,
+}
+
+impl Standing {
+    /// Whether the player's club matches `club`, ignoring case, accents and spacing
+    /// ("Rennes Paul Bert" matches "rennes paul-bert" and "Paul Bert").
+    pub fn in_club(&self, club: &str) -> bool {
+        let wanted = normalize_name(club);
+        self.club
+            .as_deref()
+            .is_some_and(|c| !wanted.is_empty() && normalize_name(c).contains(&wanted))
+    }
+}
+
+/// A parsed results table: the tie-breaks its header names, in order, and its rows.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultsTable<T> {
+    pub tiebreaks: Vec<String>,
+    pub rows: Vec<T>,
+}
+
+/// A row of an event's crosstable: the standing and every round's result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrosstableRow {
+    #[serde(flatten)]
+    pub standing: Standing,
+    pub rounds: Vec<RoundResult>,
+}
+
+/// Where each field sits in a results table, read from its header row.
+struct Columns {
+    rank: usize,
+    name: usize,
+    title: Option<usize>,
+    elo: Option<usize>,
+    category: Option<usize>,
+    federation: Option<usize>,
+    league: Option<usize>,
+    club: Option<usize>,
+    points: usize,
+    performance: Option<usize>,
+    rounds: Vec<usize>,
+    /// Header and column of each tie-break.
+    tiebreaks: Vec<(String, usize)>,
+}
+
+/// Whether a header names a round: "R 1", "R1" or "Ronde 1".
+fn is_round_label(label: &str) -> bool {
+    let number = label
+        .strip_prefix("ronde")
+        .or_else(|| label.strip_prefix('r'))
+        .map(str::trim);
+    number.is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl Columns {
+    fn from_header(cells: &[String]) -> Option<Self> {
+        let labels: Vec<String> = cells.iter().map(|c| c.to_lowercase()).collect();
+        let position = |names: &[&str]| labels.iter().position(|l| names.contains(&l.as_str()));
+        let name = position(&["nom"])?;
+        let points = position(&["pts", "pts.", "points"])?;
+        let performance = position(&["perf", "perf."]);
+        let tiebreaks = (points + 1..labels.len())
+            .filter(|i| Some(*i) != performance && !labels[*i].is_empty())
+            .map(|i| (cells[i].clone(), i))
+            .collect();
+        Some(Self {
+            rank: position(&["pl", "pl.", "rg", "rang"]).unwrap_or(0),
+            name,
+            title: name.checked_sub(1).filter(|i| labels[*i].is_empty()),
+            elo: position(&["elo"]),
+            category: position(&["cat.", "cat"]),
+            federation: position(&["fede", "féd", "fed"]),
+            league: position(&["ligue"]),
+            club: position(&["club"]),
+            points,
+            performance,
+            rounds: (0..labels.len())
+                .filter(|i| is_round_label(&labels[*i]))
+                .collect(),
+            tiebreaks,
+        })
+    }
+}
+
+/// Parses "5½", "5,5" or "5.5".
+fn parse_score(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if let Some(whole) = text.strip_suffix('½') {
+        let whole: f64 = if whole.is_empty() {
+            0.0
+        } else {
+            whole.trim().parse().ok()?
+        };
+        return Some(whole + 0.5);
+    }
+    text.replace(',', ".").parse().ok()
+}
+
+/// Parses a results table (standings or crosstable) into standings and round results.
+/// Players tied on everything have an empty or "-" rank cell, and share the rank above.
+/// The tie-breaks are those of the first header row.
+fn parse_results_table(html: &str) -> ResultsTable<(Standing, Vec<RoundResult>)> {
+    let document = Html::parse_document(html);
+    let row_selector = Selector::parse("tr").unwrap();
+    let td_selector = Selector::parse("td, th").unwrap();
+    let mut columns: Option<Columns> = None;
+    let mut tiebreaks: Option<Vec<String>> = None;
+    let mut rows = Vec::new();
+    let mut last_rank = 0;
+
+    for row in document.select(&row_selector) {
+        let cells: Vec<String> = row.select(&td_selector).map(|c| cell_text(&c)).collect();
+        if let Some(header) = Columns::from_header(&cells) {
+            tiebreaks.get_or_insert_with(|| {
+                header
+                    .tiebreaks
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            });
+            columns = Some(header);
+            continue;
+        }
+        let Some(columns) = &columns else {
+            continue;
+        };
+        let value = |index: Option<usize>| {
+            index
+                .and_then(|i| cells.get(i))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+        let (Some(name), Some(points)) = (
+            value(Some(columns.name)),
+            value(Some(columns.points)).and_then(|p| parse_score(&p)),
+        ) else {
+            continue;
+        };
+        let rank = value(Some(columns.rank))
+            .and_then(|r| r.trim_end_matches('.').parse().ok())
+            .unwrap_or(last_rank);
+        last_rank = rank;
+        let standing = Standing {
+            rank,
+            name,
+            title: value(columns.title),
+            rating: value(columns.elo).and_then(|e| Rating::parse(&e)),
+            category: value(columns.category),
+            federation: value(columns.federation),
+            league: value(columns.league),
+            club: value(columns.club),
+            points,
+            tiebreaks: columns
+                .tiebreaks
+                .iter()
+                .map(|(label, i)| Tiebreak {
+                    name: label.clone(),
+                    value: value(Some(*i)).and_then(|v| parse_score(&v)),
+                })
+                .collect(),
+            performance: value(columns.performance).and_then(|p| p.parse().ok()),
+        };
+        let mut rounds: Vec<RoundResult> = columns
+            .rounds
+            .iter()
+            .map(|i| RoundResult::parse(cells.get(*i).map_or("", String::as_str)))
+            .collect();
+        settle_bye(&mut rounds, points);
+        rows.push((standing, rounds));
+    }
+    ResultsTable {
+        tiebreaks: tiebreaks.unwrap_or_default(),
+        rows,
+    }
+}
+
+/// Parses the final standings (`Resultats.aspx?...&Action=Cl`).
+pub fn parse_standings_html(html: &str) -> ResultsTable<Standing> {
+    let table = parse_results_table(html);
+    ResultsTable {
+        tiebreaks: table.tiebreaks,
+        rows: table
+            .rows
+            .into_iter()
+            .map(|(standing, _)| standing)
+            .collect(),
+    }
+}
+
+/// Parses the crosstable (`Resultats.aspx?...&Action=Ga`).
+pub fn parse_crosstable_html(html: &str) -> ResultsTable<CrosstableRow> {
+    let table = parse_results_table(html);
+    ResultsTable {
+        tiebreaks: table.tiebreaks,
+        rows: table
+            .rows
+            .into_iter()
+            .map(|(standing, rounds)| CrosstableRow { standing, rounds })
+            .collect(),
+    }
+}
+
+fn fetch_results<T>(
+    client: &FfeClient,
+    id: EventId,
+    action: &str,
+    parse: impl Fn(&str) -> ResultsTable<T>,
+) -> Result<ResultsTable<T>, ScrapeError> {
+    let url = id.results_url(action);
+    log::debug!("Fetching results from {}", url);
+    let table = parse(&client.fetch_page(&url)?);
+    if table.rows.is_empty() {
+        return Err(ScrapeError::Layout {
+            url,
+            message: "no results table (not published yet?)".to_string(),
+        });
+    }
+    Ok(table)
+}
+
+/// Fetches the final standings of a past event.
+pub fn fetch_standings(
+    client: &FfeClient,
+    id: EventId,
+) -> Result<ResultsTable<Standing>, ScrapeError> {
+    fetch_results(client, id, "Cl", parse_standings_html)
+}
+
+/// Fetches the crosstable of a past event.
+pub fn fetch_crosstable(
+    client: &FfeClient,
+    id: EventId,
+) -> Result<ResultsTable<CrosstableRow>, ScrapeError> {
+    fetch_results(client, id, "Ga", parse_crosstable_html)
+}
+
+fn standing_header(tiebreaks: &[String]) -> Vec<String> {
+    let mut header: Vec<String> = [
+        "rank",
+        "title",
+        "name",
+        "elo",
+        "rating_type",
+        "category",
+        "federation",
+        "league",
+        "club",
+        "points",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    header.extend(tiebreaks.iter().cloned());
+    header.push("performance".to_string());
+    header
+}
+
+/// A standing's CSV fields, with its tie-break values under the `tiebreaks` columns.
+fn standing_record(standing: &Standing, tiebreaks: &[String]) -> Vec<String> {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let rating_type = standing.rating.and_then(|r| r.kind).map(|kind| match kind {
+        RatingType::Fide => "F",
+        RatingType::National => "N",
+        RatingType::Estimated => "E",
+    });
+    let mut record = vec![
+        standing.rank.to_string(),
+        text(&standing.title),
+        standing.name.clone(),
+        standing
+            .rating
+            .map(|r| r.elo.to_string())
+            .unwrap_or_default(),
+        rating_type.unwrap_or_default().to_string(),
+        text(&standing.category),
+        text(&standing.federation),
+        text(&standing.league),
+        text(&standing.club),
+        standing.points.to_string(),
+    ];
+    record.extend(tiebreaks.iter().map(|name| {
+        standing
+            .tiebreaks
+            .iter()
+            .find(|t| &t.name == name)
+            .and_then(|t| t.value)
+            .map(|v| v.to_string())
+            .unwrap_or_default()
+    }));
+    record.push(
+        standing
+            .performance
+            .map(|p| p.to_string())
+            .unwrap_or_default(),
+    );
+    record
+}
+
+fn write_csv<P: AsRef<Path>>(path: P, records: Vec<Vec<String>>) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let csv_error = |source| ExportError::Csv {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut writer = Writer::from_path(path).map_err(csv_error)?;
+    for record in records {
+        writer.write_record(&record).map_err(csv_error)?;
+    }
+    writer.flush().map_err(|e| csv_error(e.into()))
+}
+
+/// Writes standings as CSV, one column per tie-break of the table.
+pub fn write_standings_csv<P: AsRef<Path>>(
+    path: P,
+    standings: &ResultsTable<Standing>,
+) -> Result<(), ExportError> {
+    let mut records = vec![standing_header(&standings.tiebreaks)];
+    records.extend(
+        standings
+            .rows
+            .iter()
+            .map(|s| standing_record(s, &standings.tiebreaks)),
+    );
+    write_csv(path, records)
+}
+
+/// Writes a crosstable as CSV: the standings columns, then one column per round in the
+/// FFE notation ("+12B").
+pub fn write_crosstable_csv<P: AsRef<Path>>(
+    path: P,
+    crosstable: &ResultsTable<CrosstableRow>,
+) -> Result<(), ExportError> {
+    let tiebreaks = &crosstable.tiebreaks;
+    let rounds = crosstable
+        .rows
+        .iter()
+        .map(|r| r.rounds.len())
+        .max()
+        .unwrap_or(0);
+    let mut header = standing_header(tiebreaks);
+    header.extend((1..=rounds).map(|n| format!("R{}", n)));
+    let mut records = vec![header];
+    records.extend(crosstable.rows.iter().map(|row| {
+        let mut record = standing_record(&row.standing, tiebreaks);
+        record.extend(
+            (0..rounds).map(|i| row.rounds.get(i).map(|r| r.to_string()).unwrap_or_default()),
+        );
+        record
+    }));
+    write_csv(path, records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CROSSTABLE: &str = "<table>\
+        <tr><td>Pl</td><td></td><td>Nom</td><td>Elo</td><td>Club</td>\
+            <td>R 1</td><td>R 2</td><td>R 3</td><td>Pts</td><td>Tr.</td><td>Bu.</td><td>Perf</td></tr>\
+        <tr><td>1</td><td>mf</td><td>DURAND Paul</td><td>2150 F</td><td>Rennes Paul Bert</td>\
+            <td>+ 3N</td><td>EXE</td><td>+ 2B</td><td>3</td><td>2</td><td>4½</td><td>2210</td></tr>\
+        <tr><td>2</td><td></td><td>MARTIN Anne</td><td>1800 N</td><td>Vitré</td>\
+            <td>=EXE</td><td>= 3B</td><td>- 1N</td><td>1</td><td></td><td>5</td><td></td></tr>\
+        <tr><td>-</td><td></td><td>PETIT Léa</td><td>1500 E</td><td></td>\
+            <td>- 1B</td><td>= 2N</td><td></td><td>1</td><td>1</td><td>5</td><td></td></tr>\
+        </table>";
+
+    fn game(outcome: Outcome, opponent: u32, color: Option<Color>) -> RoundResult {
+        RoundResult {
+            outcome,
+            opponent: Some(opponent),
+            color,
+            bye_points: None,
+        }
+    }
+
+    #[test]
+    fn parses_games() {
+        use Color::*;
+        assert_eq!(
+            RoundResult::parse("+ 12B"),
+            game(Outcome::Win, 12, Some(White))
+        );
+        assert_eq!(
+            RoundResult::parse("=3N"),
+            game(Outcome::Draw, 3, Some(Black))
+        );
+        assert_eq!(
+            RoundResult::parse("- 7W"),
+            game(Outcome::Loss, 7, Some(White))
+        );
+        assert_eq!(
+            RoundResult::parse("> 4"),
+            game(Outcome::ForfeitWin, 4, None)
+        );
+        assert_eq!(
+            RoundResult::parse("<4"),
+            game(Outcome::ForfeitLoss, 4, None)
+        );
+    }
+
+    #[test]
+    fn byes_keep_the_points_their_sign_gives() {
+        let points = |cell| {
+            let result = RoundResult::parse(cell);
+            assert_eq!(result.outcome, Outcome::Bye, "{}", cell);
+            result.bye_points
+        };
+        assert_eq!(points("+EXE"), Some(1.0));
+        assert_eq!(points("= EXE"), Some(0.5));
+        assert_eq!(points("-exe"), Some(0.0));
+        assert_eq!(points("EXE"), None);
+    }
+
+    #[test]
+    fn empty_or_unknown_cells_are_not_played() {
+        assert_eq!(RoundResult::parse("").outcome, Outcome::NotPlayed);
+        assert_eq!(RoundResult::parse("?").outcome, Outcome::NotPlayed);
+    }
+
+    #[test]
+    fn round_result_round_trips() {
+        for cell in ["+12B", "=3N", "-7", ">4", "+EXE", "=EXE", "-EXE", "EXE", ""] {
+            assert_eq!(RoundResult::parse(cell).to_string(), cell);
+        }
+    }
+
+    #[test]
+    fn parses_half_points_either_way() {
+        assert_eq!(parse_score("5"), Some(5.0));
+        assert_eq!(parse_score("5½"), Some(5.5));
+        assert_eq!(parse_score("½"), Some(0.5));
+        assert_eq!(parse_score("5,5"), Some(5.5));
+        assert_eq!(parse_score("5.5"), Some(5.5));
+        assert_eq!(parse_score(""), None);
+        assert_eq!(parse_score("-"), None);
+    }
+
+    #[test]
+    fn parses_standings() {
+        let table = parse_standings_html(CROSSTABLE);
+        assert_eq!(table.tiebreaks, ["Tr.", "Bu."]);
+        let ranks: Vec<u32> = table.rows.iter().map(|s| s.rank).collect();
+        assert_eq!(ranks, [1, 2, 2], "a '-' rank shares the rank above");
+
+        let first = &table.rows[0];
+        assert_eq!(first.name, "DURAND Paul");
+        assert_eq!(first.title.as_deref(), Some("mf"));
+        assert_eq!(first.rating.map(|r| r.elo), Some(2150));
+        assert_eq!(first.points, 3.0);
+        assert_eq!(first.performance, Some(2210));
+        assert!(first.in_club("rennes paul-bert"));
+        assert!(!table.rows[2].in_club("Rennes"));
+    }
+
+    #[test]
+    fn empty_tiebreak_keeps_later_columns_aligned() {
+        let table = parse_standings_html(CROSSTABLE);
+        let second = &table.rows[1];
+        let values: Vec<(&str, Option<f64>)> = second
+            .tiebreaks
+            .iter()
+            .map(|t| (t.name.as_str(), t.value))
+            .collect();
+        assert_eq!(values, [("Tr.", None), ("Bu.", Some(5.0))]);
+
+        let record = standing_record(second, &table.tiebreaks);
+        let header = standing_header(&table.tiebreaks);
+        let column = |name: &str| header.iter().position(|h| h == name).unwrap();
+        assert_eq!(record[column("Tr.")], "");
+        assert_eq!(record[column("Bu.")], "5");
+    }
+
+    #[test]
+    fn header_comes_from_the_table_even_without_rows() {
+        let table = parse_standings_html(CROSSTABLE);
+        let header = standing_header(&table.tiebreaks);
+        let empty = ResultsTable::<Standing> {
+            tiebreaks: table.tiebreaks,
+            rows: Vec::new(),
+        };
+        assert_eq!(standing_header(&empty.tiebreaks), header);
+        assert!(header.iter().any(|h| h == "Bu."));
+    }
+
+    #[test]
+    fn bye_points_from_cell_or_total() {
+        let table = parse_crosstable_html(CROSSTABLE);
+        let byes: Vec<Option<f64>> = table
+            .rows
+            .iter()
+            .filter_map(|row| row.rounds.iter().find(|r| r.outcome == Outcome::Bye))
+            .map(|r| r.bye_points)
+            .collect();
+        // DURAND: 3 points, 2 from games, so the bare "EXE" was worth 1; MARTIN: "=EXE".
+        assert_eq!(byes, [Some(1.0), Some(0.5)]);
+        assert_eq!(table.rows[2].rounds[2].outcome, Outcome::NotPlayed);
+    }
+
+    #[test]
+    fn bye_left_unknown_when_total_does_not_settle_it() {
+        let cases = [
+            (vec!["EXE", "+2B"], 3.0, None),
+            (vec!["EXE", "EXE"], 1.0, None),
+            (vec!["EXE", "-2B"], 0.0, Some(0.0)),
+        ];
+        for (cells, total, expected) in cases {
+            let mut rounds: Vec<RoundResult> =
+                cells.iter().map(|c| RoundResult::parse(c)).collect();
+            settle_bye(&mut rounds, total);
+            assert_eq!(rounds[0].bye_points, expected, "{:?} for {}", cells, total);
+        }
+    }
+
+    #[test]
+    fn no_table_gives_no_rows() {
+        let table = parse_standings_html("<p>Résultats non disponibles</p>");
+        assert!(table.rows.is_empty());
+        assert!(table.tiebreaks.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use super::route::RouteSummary;
+use crate::sdk::error::CacheError;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt, fs, io::Result as IoResult, path::Path, str::FromStr};
+use std::{collections::HashMap, fmt, fs, path::Path, str::FromStr};
 
 pub type Coord = (f64, f64);
 
@@ -11,15 +12,17 @@ pub struct CityPairKey {
 }
 
 impl CityPairKey {
-    /// Creates a new, canonical CityPairKey where cities are always sorted alphabetically.
-    pub fn new(city1: &str, city2: &str) -> Self {
-        let mut cities = [city1, city2];
-        cities.sort_unstable();
+    /// Creates a key that keeps the travel direction (origin first), for asymmetric routes.
+    pub fn directed(origin: &str, destination: &str) -> Self {
         Self {
-            origin: cities[0].to_string(),
-            destination: cities[1].to_string(),
+            origin: origin.to_string(),
+            destination: destination.to_string(),
         }
     }
+
+    fn reversed(&self) -> Self {
+        Self::directed(&self.destination, &self.origin)
+    }
 }
 
 impl fmt::Display for CityPairKey {
@@ -70,26 +73,90 @@ mod city_pair_map {
     }
 }
 
+/// Geocodes and routes kept between runs, filled and read by `CacheLayer` only.
+///
+/// Routes are keyed by their end coordinates, with the direction kept. Files written
+/// by older versions keyed them by place names instead, in `routes` (either way) and
+/// `directed_routes`; those are moved under coordinates on load.
 #[derive(Serialize, Deserialize, Default)]
 pub struct GeoCache {
     geocodes: HashMap<String, Coord>,
-    #[serde(with = "city_pair_map")]
-    routes: HashMap<CityPairKey, RouteSummary>,
+    #[serde(with = "city_pair_map", default)]
+    coordinate_routes: HashMap<CityPairKey, RouteSummary>,
+    #[serde(rename = "routes", with = "city_pair_map", default, skip_serializing)]
+    legacy_routes: HashMap<CityPairKey, RouteSummary>,
+    #[serde(
+        rename = "directed_routes",
+        with = "city_pair_map",
+        default,
+        skip_serializing
+    )]
+    legacy_directed_routes: HashMap<CityPairKey, RouteSummary>,
 }
 
 impl GeoCache {
-    pub fn load_from_file<P: AsRef<Path>>(path: P) -> IoResult<Self> {
-        if path.as_ref().exists() {
-            let data = fs::read_to_string(path)?;
-            Ok(serde_json::from_str(&data)?)
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, CacheError> {
+        let path = path.as_ref();
+        if path.exists() {
+            let data = fs::read_to_string(path).map_err(|source| CacheError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            let mut cache: Self =
+                serde_json::from_str(&data).map_err(|source| CacheError::Format {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+            cache.migrate_legacy_routes();
+            Ok(cache)
         } else {
             Ok(Self::default())
         }
     }
 
-    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> IoResult<()> {
-        let data = serde_json::to_string_pretty(self)?;
-        fs::write(path, data)
+    /// Moves place-name keyed routes under the coordinates their places are cached at,
+    /// without replacing routes already keyed by coordinates. A route of `routes` was
+    /// served both ways, so it is kept for both directions unless `directed_routes` has
+    /// the other one. Routes between places without a cached geocode are dropped.
+    fn migrate_legacy_routes(&mut self) {
+        let directed = std::mem::take(&mut self.legacy_directed_routes);
+        let undirected = std::mem::take(&mut self.legacy_routes)
+            .into_iter()
+            .flat_map(|(key, summary)| [(key.reversed(), summary), (key, summary)]);
+        let (mut moved, mut dropped) = (0, 0);
+        for (key, summary) in directed.into_iter().chain(undirected) {
+            let (Some(start), Some(end)) = (
+                self.get_geocode(&key.origin),
+                self.get_geocode(&key.destination),
+            ) else {
+                dropped += 1;
+                continue;
+            };
+            self.coordinate_routes
+                .entry(coordinate_key(start, end))
+                .or_insert(summary);
+            moved += 1;
+        }
+        if moved + dropped > 0 {
+            log::info!(
+                "Moved {} place-name routes of an older cache file under their coordinates, \
+                 dropped {} without a cached geocode",
+                moved,
+                dropped
+            );
+        }
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), CacheError> {
+        let path = path.as_ref();
+        let data = serde_json::to_string_pretty(self).map_err(|source| CacheError::Format {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        fs::write(path, data).map_err(|source| CacheError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
     }
 
     pub fn get_geocode(&self, city: &str) -> Option<Coord> {
@@ -100,11 +167,75 @@ impl GeoCache {
         self.geocodes.insert(city.to_string(), coord);
     }
 
-    pub fn get_route(&self, key: &CityPairKey) -> Option<RouteSummary> {
-        self.routes.get(key).copied()
+    /// Every cached geocode, as `(query, coordinate)`.
+    pub fn geocodes(&self) -> impl Iterator<Item = (&str, Coord)> {
+        self.geocodes
+            .iter()
+            .map(|(query, coord)| (query.as_str(), *coord))
+    }
+
+    /// Drops every cached route starting or ending at `coord`, e.g. after re-geocoding
+    /// the place it stood for.
+    pub fn remove_routes_at(&mut self, coord: Coord) -> usize {
+        let point = coordinate_label(coord);
+        let before = self.coordinate_routes.len();
+        self.coordinate_routes
+            .retain(|key, _| key.origin != point && key.destination != point);
+        before - self.coordinate_routes.len()
     }
 
-    pub fn insert_route(&mut self, key: CityPairKey, summary: RouteSummary) {
-        self.routes.insert(key, summary);
+    pub fn remove_geocode(&mut self, city: &str) -> Option<Coord> {
+        self.geocodes.remove(city)
+    }
+
+    pub fn get_coordinate_route(&self, start: Coord, end: Coord) -> Option<RouteSummary> {
+        self.coordinate_routes
+            .get(&coordinate_key(start, end))
+            .copied()
+    }
+
+    pub fn insert_coordinate_route(&mut self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.coordinate_routes
+            .insert(coordinate_key(start, end), summary);
+    }
+}
+
+fn coordinate_label(coord: Coord) -> String {
+    format!("{:.6},{:.6}", coord.0, coord.1)
+}
+
+fn coordinate_key(start: Coord, end: Coord) -> CityPairKey {
+    CityPairKey::directed(&coordinate_label(start), &coordinate_label(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_place_name_routes_under_coordinates() {
+        let old_file = r#"{
+            "geocodes": {"Rennes": [-1.68, 48.11], "Vitré": [-1.2, 48.12]},
+            "routes": {
+                "Rennes::Vitré": {"distance_km": 38.0, "duration_hours": 0.5},
+                "Rennes::Brest": {"distance_km": 240.0, "duration_hours": 2.5}
+            },
+            "directed_routes": {
+                "Vitré::Rennes": {"distance_km": 40.0, "duration_hours": 0.6}
+            }
+        }"#;
+        let mut cache: GeoCache = serde_json::from_str(old_file).unwrap();
+        cache.migrate_legacy_routes();
+
+        let (rennes, vitre) = ((-1.68, 48.11), (-1.2, 48.12));
+        let there = cache.get_coordinate_route(rennes, vitre).unwrap();
+        assert_eq!(there.distance_km, 38.0);
+        let back = cache.get_coordinate_route(vitre, rennes).unwrap();
+        assert_eq!(back.distance_km, 40.0, "the directed route wins");
+        assert_eq!(cache.coordinate_routes.len(), 2, "Brest has no geocode");
+
+        let saved = serde_json::to_value(&cache).unwrap();
+        assert!(saved.get("routes").is_none());
+        assert!(saved.get("directed_routes").is_none());
     }
 }
@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::cache::{Coord, GeoCache};
+use super::geometry::Area;
+use super::poly::load_poly;
+use crate::sdk::departments::DepartmentLookup;
+use crate::sdk::error::ConfigError;
+use crate::sdk::events::Event;
+
+/// The area a routing graph was built for, as a union of `.poly` extracts.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    pub areas: Vec<Area>,
+}
+
+impl Coverage {
+    /// Loads one `.poly` file, or every `*.poly` file in a directory.
+    ///
+    /// `regions` names the extracts the local graph was built from (file stems, e.g.
+    /// `Bretagne`); loading fails when one of them is missing, since routes there would
+    /// otherwise go to the remote API without notice. An empty list skips the check.
+    pub fn load<P: AsRef<Path>>(path: P, regions: &[String]) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let coverage = Self::load_areas(path)?;
+        let missing: Vec<&str> = regions
+            .iter()
+            .filter(|region| !coverage.areas.iter().any(|a| &a.name == *region))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(ConfigError::Data {
+                path: path.to_path_buf(),
+                message: format!(
+                    "no .poly file for {} (expected by [provider] coverage_regions)",
+                    missing.join(", ")
+                ),
+            });
+        }
+        log::info!(
+            "Local graph coverage: {}",
+            coverage
+                .areas
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Ok(coverage)
+    }
+
+    fn load_areas(path: &Path) -> Result<Self, ConfigError> {
+        if !path.is_dir() {
+            return Ok(Self {
+                areas: vec![load_poly(path)?],
+            });
+        }
+        let read_error = |source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path).map_err(read_error)? {
+            let file = entry.map_err(read_error)?.path();
+            if file.extension().is_some_and(|ext| ext == "poly") {
+                files.push(file);
+            }
+        }
+        if files.is_empty() {
+            return Err(ConfigError::Data {
+                path: path.to_path_buf(),
+                message: "no .poly file in the coverage directory".to_string(),
+            });
+        }
+        files.sort();
+        let areas = files.iter().map(load_poly).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { areas })
+    }
+
+    /// The first area containing `point`.
+    pub fn area_for(&self, point: Coord) -> Option<&Area> {
+        self.areas.iter().find(|area| area.contains(point))
+    }
+
+    pub fn contains(&self, point: Coord) -> bool {
+        self.area_for(point).is_some()
+    }
+
+    /// Whether a route between the two points can be computed on the covered graph.
+    pub fn covers_route(&self, start: Coord, end: Coord) -> bool {
+        self.contains(start) && self.contains(end)
+    }
+}
+
+/// Where an event lies relative to the local graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status", content = "area")]
+pub enum CoverageStatus {
+    /// Inside the named extract.
+    Covered(String),
+    Outside,
+    /// The event's location hasn't been geocoded.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventCoverage {
+    pub event: Event,
+    pub coverage: CoverageStatus,
+    /// Both the origin and the event are covered, so the route is computed locally.
+    pub routed_locally: bool,
+}
+
+/// Reports the coverage of each event, from the coordinates already in the cache.
+pub fn event_coverage(
+    origin_query: &str,
+    events: &[Event],
+    lookup: &DepartmentLookup,
+    cache: &GeoCache,
+    coverage: &Coverage,
+) -> Vec<EventCoverage> {
+    let origin_covered = cache
+        .get_geocode(origin_query)
+        .is_some_and(|coord| coverage.contains(coord));
+    events
+        .iter()
+        .map(|event| {
+            let coord = lookup
+                .build_geocode_query(&event.location, &event.department)
+                .and_then(|query| cache.get_geocode(&query.text));
+            let status = match coord {
+                Some(coord) => match coverage.area_for(coord) {
+                    Some(area) => CoverageStatus::Covered(area.name.clone()),
+                    None => CoverageStatus::Outside,
+                },
+                None => CoverageStatus::Unknown,
+            };
+            let routed_locally = origin_covered && matches!(status, CoverageStatus::Covered(_));
+            log::info!(
+                "[COVERAGE] {} at {}: {:?}{}",
+                event.title,
+                event.location,
+                status,
+                if routed_locally {
+                    ", routed locally"
+                } else {
+                    ""
+                }
+            );
+            EventCoverage {
+                event: event.clone(),
+                coverage: status,
+                routed_locally,
+            }
+        })
+        .collect()
+}
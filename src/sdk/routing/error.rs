@@ -1,4 +1,10 @@
+use super::cache::Coord;
+use crate::sdk::util::quota::OrsEndpoint;
+use crate::sdk::util::retry::RateLimitInfo;
+use chrono::{DateTime, Utc};
+use reqwest::{StatusCode, header::HeaderMap};
 use serde::Deserialize;
+use std::time::Duration;
 use thiserror::Error;
 
 // Helper structs to parse the JSON error response from ORS
@@ -12,11 +18,110 @@ pub struct OrsErrorPayload {
     pub error: OrsErrorDetail,
 }
 
+/// Errors from turning a place into coordinates.
+#[derive(Error, Debug)]
+pub enum GeocodeError {
+    #[error("No geocode results for '{0}'")]
+    NoResults(String),
+
+    #[error("No routable point found near {0:?}")]
+    NoRoutablePoint(Coord),
+
+    #[error("Invalid postal code: {0}")]
+    InvalidPostalCode(String),
+
+    #[error("Invalid INSEE code: {0}")]
+    InvalidInseeCode(String),
+
+    #[error("Unknown department code: {0}")]
+    UnknownDepartment(String),
+
+    /// Every result lies outside the department named in the query (homonymous communes).
+    #[error("No result for '{query}' in {expected} (found: {found:?})")]
+    DepartmentMismatch {
+        query: String,
+        expected: String,
+        found: Vec<String>,
+    },
+
+    #[error("Geocoding service returned {status} for {url}: {body}")]
+    Service {
+        url: String,
+        status: StatusCode,
+        body: String,
+    },
+
+    #[error("Geocoding request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Failed to parse geocoding response: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl GeocodeError {
+    /// Whether the same call may succeed if attempted again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GeocodeError::Service { status, .. } => is_retryable_status(*status),
+            GeocodeError::Request(e) => is_retryable_request(e),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum RoutingError {
     #[error("A point was not routable on the road network")]
     UnroutablePoint,
 
+    /// ORS 2004: the route exceeds the server's maximum distance.
+    #[error("Route too long to calculate: {message}")]
+    RouteTooLong { message: String },
+
+    /// ORS 2010: no routable point near one of the coordinates.
+    #[error("Point not found: {message}")]
+    PointNotFound { message: String },
+
+    /// ORS 2009: both points are routable but not connected.
+    #[error("Route not found: {message}")]
+    RouteNotFound { message: String },
+
+    /// ORS 2000-2003, 2006, 2007: the request itself is malformed.
+    #[error("Invalid parameters (Code {code}): {message}")]
+    InvalidParameters { code: u32, message: String },
+
+    /// HTTP 403 whose body says the API key's quota is used up.
+    #[error("Quota exceeded: {message}")]
+    QuotaExceeded { message: String },
+
+    /// HTTP 401, or a 403 not about the quota: the API key is missing, invalid or revoked.
+    #[error("ORS rejected the API key ({status}): {message}")]
+    Unauthorized { status: StatusCode, message: String },
+
+    /// The local quota tracker has no calls left today; nothing was sent.
+    #[error("Daily ORS {endpoint} quota used up (resets at {resets_at})")]
+    DailyQuotaExhausted {
+        endpoint: OrsEndpoint,
+        resets_at: DateTime<Utc>,
+    },
+
+    /// HTTP 429: too many requests in a short time.
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// Delay requested by the server's rate-limit headers.
+        retry_after: Option<Duration>,
+    },
+
+    /// HTTP 5xx from the routing server, whatever ORS error code the body carries.
+    #[error("Server error ({status}): {message}")]
+    ServerError {
+        status: StatusCode,
+        /// The ORS error code, when the body has one (e.g. 2099 for an internal error).
+        code: Option<u32>,
+        message: String,
+    },
+
     // This variant hold the structured error from the API
     #[error("API Error (Code {code}): {message}")]
     ApiError { code: u32, message: String },
@@ -31,6 +136,170 @@ pub enum RoutingError {
     #[error("Failed to parse JSON response: {0}")]
     ParseError(#[from] serde_json::Error),
 
+    #[error("Geocoding failed: {0}")]
+    Geocode(#[from] GeocodeError),
+
+    /// The ORS instance answers but is still building its graphs.
+    #[error("ORS at {url} is not ready yet (graphs still building?)")]
+    NotReady { url: String },
+
+    /// The configured profile isn't enabled on the ORS instance.
+    #[error("Profile '{profile}' is not enabled on this ORS instance (available: {available:?})")]
+    ProfileUnavailable {
+        profile: String,
+        available: Vec<String>,
+    },
+
+    /// A circuit breaker is failing calls fast after repeated backend failures.
+    #[error("Circuit for {name} is open, retrying in {retry_in:?}")]
+    CircuitOpen { name: String, retry_in: Duration },
+
     #[error("Generic error: {0}")]
     Generic(String),
 }
+
+impl RoutingError {
+    /// Maps an ORS error code to its named variant.
+    pub fn from_ors_code(code: u32, message: String) -> Self {
+        match code {
+            2004 => RoutingError::RouteTooLong { message },
+            2009 => RoutingError::RouteNotFound { message },
+            2010 => RoutingError::PointNotFound { message },
+            2000..=2003 | 2006 | 2007 => RoutingError::InvalidParameters { code, message },
+            _ => RoutingError::ApiError { code, message },
+        }
+    }
+
+    /// Builds the error for a non-success ORS response.
+    ///
+    /// The HTTP status decides the variant, so a 5xx stays a retryable server error even
+    /// when its body is an ORS error payload; the payload's code and message are kept as
+    /// detail. Only other statuses are mapped by their ORS code.
+    pub fn from_response(status: StatusCode, headers: &HeaderMap, body: String) -> Self {
+        let payload = serde_json::from_str::<OrsErrorPayload>(&body).ok();
+        let code = payload.as_ref().map(|p| p.error.code);
+        let message = match payload {
+            Some(payload) => payload.error.message,
+            None => body,
+        };
+        match status {
+            status if status.is_server_error() => RoutingError::ServerError {
+                status,
+                code,
+                message,
+            },
+            StatusCode::TOO_MANY_REQUESTS => RoutingError::RateLimited {
+                message,
+                retry_after: RateLimitInfo::from_headers(headers).wait_hint(),
+            },
+            StatusCode::FORBIDDEN if message.to_lowercase().contains("quota") => {
+                RoutingError::QuotaExceeded { message }
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                RoutingError::Unauthorized { status, message }
+            }
+            _ => match code {
+                Some(code) => Self::from_ors_code(code, message),
+                None => RoutingError::RawApiError(message),
+            },
+        }
+    }
+
+    /// The variant, without its data; used to configure which errors are retried.
+    pub fn kind(&self) -> RoutingErrorKind {
+        match self {
+            RoutingError::UnroutablePoint => RoutingErrorKind::UnroutablePoint,
+            RoutingError::RouteTooLong { .. } => RoutingErrorKind::RouteTooLong,
+            RoutingError::PointNotFound { .. } => RoutingErrorKind::PointNotFound,
+            RoutingError::RouteNotFound { .. } => RoutingErrorKind::RouteNotFound,
+            RoutingError::InvalidParameters { .. } => RoutingErrorKind::InvalidParameters,
+            RoutingError::QuotaExceeded { .. } => RoutingErrorKind::QuotaExceeded,
+            RoutingError::Unauthorized { .. } => RoutingErrorKind::Unauthorized,
+            RoutingError::DailyQuotaExhausted { .. } => RoutingErrorKind::DailyQuotaExhausted,
+            RoutingError::RateLimited { .. } => RoutingErrorKind::RateLimited,
+            RoutingError::ServerError { .. } => RoutingErrorKind::ServerError,
+            RoutingError::ApiError { .. } => RoutingErrorKind::Api,
+            RoutingError::RawApiError(_) => RoutingErrorKind::RawApi,
+            RoutingError::RequestError(_) => RoutingErrorKind::Request,
+            RoutingError::ParseError(_) => RoutingErrorKind::Parse,
+            RoutingError::Geocode(_) => RoutingErrorKind::Geocode,
+            RoutingError::NotReady { .. } => RoutingErrorKind::NotReady,
+            RoutingError::ProfileUnavailable { .. } => RoutingErrorKind::ProfileUnavailable,
+            RoutingError::CircuitOpen { .. } => RoutingErrorKind::CircuitOpen,
+            RoutingError::Generic(_) => RoutingErrorKind::Generic,
+        }
+    }
+
+    /// Delay requested by the server before trying again, if it sent one.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RoutingError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Whether the API quota is used up, so no further call can succeed today.
+    pub fn is_quota_exhausted(&self) -> bool {
+        matches!(
+            self,
+            RoutingError::QuotaExceeded { .. } | RoutingError::DailyQuotaExhausted { .. }
+        )
+    }
+
+    /// Whether the backend itself is unreachable or failing, as opposed to rejecting
+    /// this particular request. Used by circuit breakers and fallbacks.
+    pub fn is_backend_unavailable(&self) -> bool {
+        matches!(
+            self,
+            RoutingError::RequestError(_)
+                | RoutingError::ServerError { .. }
+                | RoutingError::NotReady { .. }
+                | RoutingError::CircuitOpen { .. }
+        )
+    }
+
+    /// Whether the same call may succeed if attempted again later. Routing calls retry
+    /// these unless `[retry] retry_on` lists the kinds to retry.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RoutingError::RateLimited { .. } => true,
+            RoutingError::ServerError { status, .. } => is_retryable_status(*status),
+            RoutingError::RequestError(e) => is_retryable_request(e),
+            RoutingError::Geocode(e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
+/// Data-less mirror of `RoutingError`, e.g. `retry_on = ["rate_limited", "server_error"]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingErrorKind {
+    UnroutablePoint,
+    RouteTooLong,
+    PointNotFound,
+    RouteNotFound,
+    InvalidParameters,
+    QuotaExceeded,
+    Unauthorized,
+    DailyQuotaExhausted,
+    RateLimited,
+    ServerError,
+    Api,
+    RawApi,
+    Request,
+    Parse,
+    Geocode,
+    NotReady,
+    ProfileUnavailable,
+    CircuitOpen,
+    Generic,
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || matches!(status.as_u16(), 500 | 502 | 503 | 504)
+}
+
+pub(crate) fn is_retryable_request(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.status().is_some_and(is_retryable_status)
+}
@@ -1,22 +1,67 @@
-use super::cache::{Coord, GeoCache};
+use std::fmt;
+
+use super::cache::Coord;
+use super::error::{GeocodeError, RoutingError};
+use super::geometry::BoundingBox;
 use super::service::RoutingProvider;
-use std::error::Error;
 
-/// Gets coordinates for a city, using a cache to avoid redundant API calls.
-pub fn get_or_cache_geocode(
-    city: &str,
+/// A place to geocode: the text sent to the geocoder, which is also its cache key, and
+/// the department the result must lie in when it is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeocodeQuery {
+    pub text: String,
+    pub department: Option<DepartmentHint>,
+}
+
+/// What the geocoder is told about the department a place lies in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepartmentHint {
+    pub code: String,
+    pub name: String,
+    /// Centre of the department, sent as the search's focus point.
+    pub centroid: Option<Coord>,
+    /// Bounding box of the department's outline, when boundaries are loaded.
+    pub bbox: Option<BoundingBox>,
+}
+
+impl GeocodeQuery {
+    /// A query without a department, e.g. the "lat,lon" of an origin given by coordinates.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            department: None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+impl fmt::Display for GeocodeQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Gets coordinates for a geocode query. A "lat,lon" query, as built for an origin given
+/// by coordinates, is read as is; anything else goes to the provider, whose cache layer
+/// avoids redundant API calls.
+pub fn geocode_query(
+    query: &GeocodeQuery,
     provider: &dyn RoutingProvider,
-    cache: &mut GeoCache,
-) -> Result<Coord, Box<dyn Error>> {
-    if let Some(coord) = cache.get_geocode(city) {
-        log::debug!("Cache hit for geocode: {}", city);
+) -> Result<Coord, RoutingError> {
+    if let Some(coord) = parse_lat_lon(&query.text) {
         return Ok(coord);
     }
+    provider.geocode(query)
+}
 
-    log::debug!("Cache miss for geocode: {}. Calling provider.", city);
-    let coord = provider.geocode(city)?;
-    cache.insert_geocode(city, coord);
-    Ok(coord)
+fn parse_lat_lon(query: &str) -> Option<Coord> {
+    let (lat, lon) = query.split_once(',')?;
+    let lat: f64 = lat.trim().parse().ok()?;
+    let lon: f64 = lon.trim().parse().ok()?;
+    Some((lon, lat))
 }
 
 /// Finds the nearest routable coordinate to a given point.
@@ -24,7 +69,7 @@ pub fn find_routable_coordinates(
     lon: f64,
     lat: f64,
     provider: &dyn RoutingProvider,
-) -> Result<Coord, Box<dyn Error>> {
+) -> Result<Coord, RoutingError> {
     let candidate_coords = provider.reverse_geocode((lon, lat))?;
     for coord in candidate_coords {
         if provider.is_routable(coord)? {
@@ -37,5 +82,5 @@ pub fn find_routable_coordinates(
             return Ok(coord);
         }
     }
-    Err("No routable point found among candidates".into())
+    Err(GeocodeError::NoRoutablePoint((lon, lat)).into())
 }
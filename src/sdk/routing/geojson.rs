@@ -0,0 +1,182 @@
+//! Reads `Polygon`/`MultiPolygon` features of a GeoJSON `FeatureCollection` as `Area`s,
+//! e.g. the department outlines of <https://github.com/gregoiredavid/france-geojson>.
+
+use serde_json::Value;
+
+use super::cache::Coord;
+use super::geometry::{Area, Ring};
+
+fn parse_ring(value: &Value) -> Result<Ring, String> {
+    value
+        .as_array()
+        .ok_or("ring is not an array")?
+        .iter()
+        .map(|point| match point.as_array().map(Vec::as_slice) {
+            Some([lon, lat, ..]) => match (lon.as_f64(), lat.as_f64()) {
+                (Some(lon), Some(lat)) => Ok::<Coord, String>((lon, lat)),
+                _ => Err(format!("invalid position {}", point)),
+            },
+            _ => Err(format!("invalid position {}", point)),
+        })
+        .collect()
+}
+
+/// Adds one polygon (outer ring, then holes) to `outers` and `holes`.
+fn parse_polygon(
+    value: &Value,
+    outers: &mut Vec<Ring>,
+    holes: &mut Vec<Ring>,
+) -> Result<(), String> {
+    let rings = value.as_array().ok_or("polygon is not an array")?;
+    for (i, ring) in rings.iter().enumerate() {
+        let ring = parse_ring(ring)?;
+        if i == 0 {
+            outers.push(ring);
+        } else {
+            holes.push(ring);
+        }
+    }
+    Ok(())
+}
+
+/// Parses a feature's geometry into an area called `name`.
+pub fn parse_geometry(name: &str, geometry: &Value) -> Result<Area, String> {
+    let coordinates = &geometry["coordinates"];
+    let (mut outers, mut holes) = (Vec::new(), Vec::new());
+    match geometry["type"].as_str() {
+        Some("Polygon") => parse_polygon(coordinates, &mut outers, &mut holes)?,
+        Some("MultiPolygon") => {
+            for polygon in coordinates
+                .as_array()
+                .ok_or("multipolygon is not an array")?
+            {
+                parse_polygon(polygon, &mut outers, &mut holes)?;
+            }
+        }
+        other => return Err(format!("unsupported geometry type {:?}", other)),
+    }
+    Ok(Area::new(name, outers, holes))
+}
+
+/// Parses every feature of a collection, naming each area after its `name_property`.
+pub fn parse_feature_collection(text: &str, name_property: &str) -> Result<Vec<Area>, String> {
+    let collection: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    collection["features"]
+        .as_array()
+        .ok_or("not a FeatureCollection")?
+        .iter()
+        .map(|feature| {
+            let name = match &feature["properties"][name_property] {
+                Value::String(name) => name.clone(),
+                Value::Number(number) => number.to_string(),
+                _ => return Err(format!("feature without a '{}' property", name_property)),
+            };
+            parse_geometry(&name, &feature["geometry"]).map_err(|e| format!("{}: {}", name, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": {"code": "35", "nom": "Ille-et-Vilaine"},
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [
+                        [[0, 0], [4, 0], [4, 4], [0, 4], [0, 0]],
+                        [[1, 1], [2, 1], [2, 2], [1, 2], [1, 1]]
+                    ]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": {"code": 29, "nom": "Finistère"},
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[10, 10], [11, 10], [11, 11], [10, 10]]],
+                        [[[20, 20], [21, 20], [21, 21], [20, 20]]]
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    fn geometry_error(json: &str) -> String {
+        let geometry: Value = serde_json::from_str(json).unwrap();
+        parse_geometry("test", &geometry).unwrap_err()
+    }
+
+    #[test]
+    fn parses_polygons_and_multipolygons() {
+        let areas = parse_feature_collection(COLLECTION, "code").unwrap();
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[0].outers.len(), 1);
+        assert_eq!(areas[0].holes.len(), 1);
+        assert_eq!(areas[0].outers[0][1], (4.0, 0.0));
+        assert_eq!(areas[1].outers.len(), 2);
+        assert!(areas[1].holes.is_empty());
+
+        assert!(areas[0].contains((3.0, 3.0)));
+        assert!(!areas[0].contains((1.5, 1.5)), "inside the hole");
+        assert!(areas[1].contains((20.8, 20.2)));
+        assert!(!areas[1].contains((15.0, 15.0)), "between the polygons");
+    }
+
+    #[test]
+    fn names_areas_after_the_given_property() {
+        let codes = parse_feature_collection(COLLECTION, "code").unwrap();
+        assert_eq!(codes[0].name, "35");
+        assert_eq!(codes[1].name, "29", "numeric property");
+
+        let names = parse_feature_collection(COLLECTION, "nom").unwrap();
+        assert_eq!(names[1].name, "Finistère");
+    }
+
+    #[test]
+    fn rejects_malformed_geometries() {
+        assert!(geometry_error(r#"{"type": "Point", "coordinates": [0, 0]}"#).contains("Point"));
+        assert!(geometry_error(r#"{"coordinates": []}"#).starts_with("unsupported geometry type"));
+        assert_eq!(
+            geometry_error(r#"{"type": "Polygon", "coordinates": 3}"#),
+            "polygon is not an array"
+        );
+        assert_eq!(
+            geometry_error(r#"{"type": "Polygon", "coordinates": [3]}"#),
+            "ring is not an array"
+        );
+        assert_eq!(
+            geometry_error(r#"{"type": "Polygon", "coordinates": [[[0]]]}"#),
+            "invalid position [0]"
+        );
+        assert_eq!(
+            geometry_error(r#"{"type": "MultiPolygon", "coordinates": {}}"#),
+            "multipolygon is not an array"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_collections() {
+        let cases = [
+            ("", "not JSON"),
+            (r#"{"type": "FeatureCollection"}"#, "no features"),
+            (
+                r#"{"features": [{"properties": {}, "geometry": {"type": "Polygon", "coordinates": []}}]}"#,
+                "feature without the name property",
+            ),
+            (
+                r#"{"features": [{"properties": {"code": "35"}, "geometry": {"type": "Point"}}]}"#,
+                "invalid geometry",
+            ),
+        ];
+        for (text, case) in cases {
+            assert!(parse_feature_collection(text, "code").is_err(), "{}", case);
+        }
+    }
+}
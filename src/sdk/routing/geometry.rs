@@ -0,0 +1,126 @@
+use super::cache::Coord;
+
+/// A closed ring of `(lon, lat)` points; the last point need not repeat the first.
+pub type Ring = Vec<Coord>;
+
+/// `(min_lon, min_lat, max_lon, max_lat)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BoundingBox {
+    fn of<'a>(points: impl IntoIterator<Item = &'a Coord>) -> Option<Self> {
+        points.into_iter().fold(None, |bbox, &(lon, lat)| {
+            Some(match bbox {
+                None => Self {
+                    min_lon: lon,
+                    min_lat: lat,
+                    max_lon: lon,
+                    max_lat: lat,
+                },
+                Some(b) => Self {
+                    min_lon: b.min_lon.min(lon),
+                    min_lat: b.min_lat.min(lat),
+                    max_lon: b.max_lon.max(lon),
+                    max_lat: b.max_lat.max(lat),
+                },
+            })
+        })
+    }
+
+    pub fn contains(&self, (lon, lat): Coord) -> bool {
+        lon >= self.min_lon && lon <= self.max_lon && lat >= self.min_lat && lat <= self.max_lat
+    }
+}
+
+/// A named area made of outer rings minus holes, as in Osmosis `.poly` files: a point
+/// is inside when it is in any outer ring and in none of the holes.
+#[derive(Debug, Clone)]
+pub struct Area {
+    pub name: String,
+    pub outers: Vec<Ring>,
+    pub holes: Vec<Ring>,
+    bbox: Option<BoundingBox>,
+}
+
+impl Area {
+    pub fn new(name: impl Into<String>, outers: Vec<Ring>, holes: Vec<Ring>) -> Self {
+        let bbox = BoundingBox::of(outers.iter().flatten());
+        Self {
+            name: name.into(),
+            outers,
+            holes,
+            bbox,
+        }
+    }
+
+    pub fn bbox(&self) -> Option<BoundingBox> {
+        self.bbox
+    }
+
+    pub fn contains(&self, point: Coord) -> bool {
+        self.bbox.is_some_and(|b| b.contains(point))
+            && self.outers.iter().any(|ring| ring_contains(ring, point))
+            && !self.holes.iter().any(|ring| ring_contains(ring, point))
+    }
+}
+
+/// Whether `(x, y)` lies on the segment from `a` to `b`.
+fn on_segment((ax, ay): Coord, (bx, by): Coord, (x, y): Coord) -> bool {
+    let cross = (bx - ax) * (y - ay) - (by - ay) * (x - ax);
+    cross.abs() <= f64::EPSILON * (bx - ax).abs().max((by - ay).abs()).max(1.0)
+        && x >= ax.min(bx)
+        && x <= ax.max(bx)
+        && y >= ay.min(by)
+        && y <= ay.max(by)
+}
+
+/// Even-odd ray casting test. Points on the boundary count as inside.
+pub fn ring_contains(ring: &[Coord], (x, y): Coord) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let (xi, yi) = ring[i];
+        let (xj, yj) = ring[j];
+        if on_segment(ring[j], ring[i], (x, y)) {
+            return true;
+        }
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_contains_cases() {
+        let triangle: Ring = vec![(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+        let cases = [
+            ((1.0, 1.0), true, "inside"),
+            ((3.0, 3.0), false, "outside, within the bounding box"),
+            ((-1.0, 1.0), false, "outside, left"),
+            ((2.0, 0.0), true, "on the bottom edge"),
+            ((2.0, 2.0), true, "on the diagonal edge"),
+            ((0.0, 2.0), true, "on the closing edge"),
+            ((4.0, 0.0), true, "on a vertex"),
+            ((5.0, 0.0), false, "on the edge's line, past its end"),
+        ];
+        for (point, expected, case) in cases {
+            assert_eq!(ring_contains(&triangle, point), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn empty_ring_contains_nothing() {
+        assert!(!ring_contains(&[], (0.0, 0.0)));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::sdk::routing::cache::{Coord, GeoCache};
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use std::sync::{Arc, Mutex};
+
+/// Answers geocodes and directions from a `GeoCache` before asking the inner provider.
+/// It is the only place the routing code caches anything.
+///
+/// Directions are keyed by coordinates, so this works for any caller, not only
+/// `get_road_distance`. A route found between snapped points is also kept under the
+/// points first asked for, so later runs skip the failing call and the snapping. The
+/// cache is shared so the caller can save it after the run.
+pub struct CacheLayer<P> {
+    inner: P,
+    cache: Arc<Mutex<GeoCache>>,
+}
+
+impl<P: RoutingProvider> CacheLayer<P> {
+    pub fn new(inner: P, cache: Arc<Mutex<GeoCache>>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for CacheLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        if let Some(coord) = self.cache.lock().unwrap().get_geocode(&query.text) {
+            return Ok(coord);
+        }
+        let coord = self.inner.geocode(query)?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert_geocode(&query.text, coord);
+        Ok(coord)
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.inner.reverse_geocode(coord)
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.inner.is_routable(coord)
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        if let Some(summary) = self.cache.lock().unwrap().get_coordinate_route(start, end) {
+            return Ok(summary);
+        }
+        let summary = self.inner.get_directions(start, end)?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert_coordinate_route(start, end, summary);
+        Ok(summary)
+    }
+
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert_coordinate_route(start, end, summary);
+        self.inner.record_route(start, end, summary)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The `[circuit_breaker]` section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive backend failures that open the circuit.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a probe call is let through.
+    pub cooldown_secs: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CircuitState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen,
+}
+
+/// Fails calls fast once the inner provider has failed `failure_threshold` times in a
+/// row, then lets a single probe through after the cooldown.
+///
+/// Only unreachable or failing backends count (see `RoutingError::is_backend_unavailable`);
+/// errors such as "route not found" mean the backend is up.
+pub struct CircuitBreakerLayer<P> {
+    inner: P,
+    name: String,
+    config: CircuitBreakerConfig,
+    state: Mutex<CircuitState>,
+}
+
+impl<P: RoutingProvider> CircuitBreakerLayer<P> {
+    pub fn new(inner: P, name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner,
+            name: name.into(),
+            config,
+            state: Mutex::new(CircuitState::Closed { failures: 0 }),
+        }
+    }
+
+    fn call<T>(&self, op: impl FnOnce() -> Result<T, RoutingError>) -> Result<T, RoutingError> {
+        {
+            let mut state = self.state.lock().unwrap();
+            match *state {
+                CircuitState::Open { until } => {
+                    let now = Instant::now();
+                    if now < until {
+                        return Err(RoutingError::CircuitOpen {
+                            name: self.name.clone(),
+                            retry_in: until - now,
+                        });
+                    }
+                    log::info!("[CIRCUIT] {} half-open, probing", self.name);
+                    *state = CircuitState::HalfOpen;
+                }
+                CircuitState::HalfOpen | CircuitState::Closed { .. } => {}
+            }
+        }
+
+        let result = op();
+        let failed = matches!(&result, Err(e) if e.is_backend_unavailable());
+        let mut state = self.state.lock().unwrap();
+        *state = match (*state, failed) {
+            (CircuitState::HalfOpen, false) => {
+                log::info!("[CIRCUIT] {} closed, backend is back", self.name);
+                CircuitState::Closed { failures: 0 }
+            }
+            (_, false) => CircuitState::Closed { failures: 0 },
+            (CircuitState::HalfOpen, true) => {
+                log::warn!(
+                    "[CIRCUIT] {} probe failed, open for another {}s",
+                    self.name,
+                    self.config.cooldown_secs
+                );
+                self.open()
+            }
+            (CircuitState::Closed { failures }, true)
+                if failures + 1 >= self.config.failure_threshold =>
+            {
+                log::warn!(
+                    "[CIRCUIT] {} open after {} consecutive failures, failing fast for {}s",
+                    self.name,
+                    failures + 1,
+                    self.config.cooldown_secs
+                );
+                self.open()
+            }
+            (CircuitState::Closed { failures }, true) => CircuitState::Closed {
+                failures: failures + 1,
+            },
+            (open @ CircuitState::Open { .. }, true) => open,
+        };
+        result
+    }
+
+    fn open(&self) -> CircuitState {
+        CircuitState::Open {
+            until: Instant::now() + Duration::from_secs(self.config.cooldown_secs),
+        }
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for CircuitBreakerLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.call(|| self.inner.geocode(query))
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.call(|| self.inner.reverse_geocode(coord))
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.call(|| self.inner.is_routable(coord))
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        self.call(|| self.inner.get_directions(start, end))
+    }
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.inner.record_route(start, end, summary)
+    }
+}
@@ -0,0 +1,72 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+
+/// Sends a call to `fallback` when `primary` is unavailable (down, failing, or behind
+/// an open circuit). Other errors are returned as they are.
+pub struct FallbackLayer<P> {
+    primary: P,
+    fallback: Box<dyn RoutingProvider>,
+}
+
+impl<P: RoutingProvider> FallbackLayer<P> {
+    pub fn new(primary: P, fallback: Box<dyn RoutingProvider>) -> Self {
+        Self { primary, fallback }
+    }
+
+    fn call<T>(
+        &self,
+        method: &str,
+        primary: impl FnOnce() -> Result<T, RoutingError>,
+        fallback: impl FnOnce() -> Result<T, RoutingError>,
+    ) -> Result<T, RoutingError> {
+        match primary() {
+            Err(e) if e.is_backend_unavailable() => {
+                log::warn!("[FALLBACK] {} via fallback provider: {}", method, e);
+                fallback()
+            }
+            result => result,
+        }
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for FallbackLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.call(
+            "geocode",
+            || self.primary.geocode(query),
+            || self.fallback.geocode(query),
+        )
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.call(
+            "reverse_geocode",
+            || self.primary.reverse_geocode(coord),
+            || self.fallback.reverse_geocode(coord),
+        )
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.call(
+            "is_routable",
+            || self.primary.is_routable(coord),
+            || self.fallback.is_routable(coord),
+        )
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        self.call(
+            "get_directions",
+            || self.primary.get_directions(start, end),
+            || self.fallback.get_directions(start, end),
+        )
+    }
+
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.primary.record_route(start, end, summary);
+        self.fallback.record_route(start, end, summary);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Call counters for one provider method.
+#[derive(Debug, Default)]
+pub struct MethodMetrics {
+    calls: AtomicU64,
+    errors: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+impl MethodMetrics {
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Mean time per call, including failed ones.
+    pub fn mean_latency(&self) -> Duration {
+        let calls = self.calls().max(1);
+        Duration::from_micros(self.total_micros.load(Ordering::Relaxed) / calls)
+    }
+
+    fn record<T>(&self, started: Instant, result: &Result<T, RoutingError>) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if result.is_err() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.total_micros
+            .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Counters for every provider method, shared with whoever reports them.
+#[derive(Debug, Default)]
+pub struct ProviderMetrics {
+    pub geocode: MethodMetrics,
+    pub reverse_geocode: MethodMetrics,
+    pub is_routable: MethodMetrics,
+    pub get_directions: MethodMetrics,
+}
+
+impl ProviderMetrics {
+    pub fn log_summary(&self) {
+        for (name, metrics) in [
+            ("geocode", &self.geocode),
+            ("reverse_geocode", &self.reverse_geocode),
+            ("is_routable", &self.is_routable),
+            ("get_directions", &self.get_directions),
+        ] {
+            if metrics.calls() > 0 {
+                log::info!(
+                    "[METRICS] {}: {} calls, {} errors, {:?} mean",
+                    name,
+                    metrics.calls(),
+                    metrics.errors(),
+                    metrics.mean_latency()
+                );
+            }
+        }
+    }
+}
+
+/// Counts calls, failures and time spent in the inner provider.
+pub struct MetricsLayer<P> {
+    inner: P,
+    metrics: Arc<ProviderMetrics>,
+}
+
+impl<P: RoutingProvider> MetricsLayer<P> {
+    pub fn new(inner: P, metrics: Arc<ProviderMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for MetricsLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        let started = Instant::now();
+        let result = self.inner.geocode(query);
+        self.metrics.geocode.record(started, &result);
+        result
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        let started = Instant::now();
+        let result = self.inner.reverse_geocode(coord);
+        self.metrics.reverse_geocode.record(started, &result);
+        result
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        let started = Instant::now();
+        let result = self.inner.is_routable(coord);
+        self.metrics.is_routable.record(started, &result);
+        result
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        let started = Instant::now();
+        let result = self.inner.get_directions(start, end);
+        self.metrics.get_directions.record(started, &result);
+        result
+    }
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.inner.record_route(start, end, summary)
+    }
+}
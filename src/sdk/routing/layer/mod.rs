@@ -0,0 +1,106 @@
+//! Decorators that add caching, rate limiting, retries, metrics, tracing, circuit
+//! breaking or a fallback to any `RoutingProvider`.
+
+pub mod cache;
+pub mod circuit;
+pub mod fallback;
+pub mod metrics;
+pub mod rate_limit;
+pub mod retry;
+pub mod trace;
+
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+use super::cache::GeoCache;
+use super::service::RoutingProvider;
+use crate::sdk::util::rate_limit::Limiter;
+use crate::sdk::util::retry::RetryPolicy;
+
+pub use cache::CacheLayer;
+pub use circuit::{CircuitBreakerConfig, CircuitBreakerLayer};
+pub use fallback::FallbackLayer;
+pub use metrics::{MetricsLayer, ProviderMetrics};
+pub use rate_limit::RateLimitLayer;
+pub use retry::RetryLayer;
+pub use trace::TraceLayer;
+
+/// A layer that can be listed in the `[provider] layers` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerKind {
+    Trace,
+    Metrics,
+    Cache,
+    Retry,
+    RateLimit,
+    CircuitBreaker,
+    /// Uses the provider named by `[provider] fallback` when the stack below is unavailable.
+    Fallback,
+}
+
+/// Wraps a provider in layers, one call at a time. Each call wraps the current stack,
+/// so the last layer added is the first to see a request.
+pub struct ProviderStack {
+    provider: Box<dyn RoutingProvider>,
+    metrics: Option<Arc<ProviderMetrics>>,
+}
+
+impl ProviderStack {
+    pub fn new(provider: impl RoutingProvider + 'static) -> Self {
+        Self {
+            provider: Box::new(provider),
+            metrics: None,
+        }
+    }
+
+    pub fn trace(mut self, name: impl Into<String>) -> Self {
+        self.provider = Box::new(TraceLayer::new(self.provider, name));
+        self
+    }
+
+    pub fn metrics(mut self) -> Self {
+        let metrics = self.metrics.get_or_insert_with(Default::default).clone();
+        self.provider = Box::new(MetricsLayer::new(self.provider, metrics));
+        self
+    }
+
+    /// `cache` is shared so the caller can seed it, inspect it and save it after the run.
+    pub fn cache(mut self, cache: Arc<Mutex<GeoCache>>) -> Self {
+        self.provider = Box::new(CacheLayer::new(self.provider, cache));
+        self
+    }
+
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.provider = Box::new(RetryLayer::new(self.provider, policy));
+        self
+    }
+
+    pub fn rate_limit(mut self, limiter: Limiter, host: impl Into<String>) -> Self {
+        self.provider = Box::new(RateLimitLayer::new(self.provider, limiter, host));
+        self
+    }
+
+    pub fn circuit_breaker(
+        mut self,
+        name: impl Into<String>,
+        config: CircuitBreakerConfig,
+    ) -> Self {
+        self.provider = Box::new(CircuitBreakerLayer::new(self.provider, name, config));
+        self
+    }
+
+    pub fn fallback(mut self, fallback: Box<dyn RoutingProvider>) -> Self {
+        self.provider = Box::new(FallbackLayer::new(self.provider, fallback));
+        self
+    }
+
+    /// Counters of the metrics layer, if one was added.
+    pub fn metrics_handle(&self) -> Option<Arc<ProviderMetrics>> {
+        self.metrics.clone()
+    }
+
+    pub fn build(self) -> Box<dyn RoutingProvider> {
+        self.provider
+    }
+}
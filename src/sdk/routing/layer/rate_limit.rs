@@ -0,0 +1,55 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use crate::sdk::util::quota::OrsEndpoint;
+use crate::sdk::util::rate_limit::{LimitKey, Limiter};
+
+/// Waits for the limiter before each call, keyed by `host` and the ORS endpoint the call
+/// goes to, as the providers' built-in limiter does.
+pub struct RateLimitLayer<P> {
+    inner: P,
+    limiter: Limiter,
+    host: String,
+}
+
+impl<P: RoutingProvider> RateLimitLayer<P> {
+    pub fn new(inner: P, limiter: Limiter, host: impl Into<String>) -> Self {
+        Self {
+            inner,
+            limiter,
+            host: host.into(),
+        }
+    }
+
+    fn wait(&self, endpoint: OrsEndpoint) {
+        self.limiter
+            .wait(&LimitKey::new(self.host.as_str(), endpoint.to_string()));
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for RateLimitLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.wait(OrsEndpoint::Geocode);
+        self.inner.geocode(query)
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.wait(OrsEndpoint::ReverseGeocode);
+        self.inner.reverse_geocode(coord)
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.wait(OrsEndpoint::Directions);
+        self.inner.is_routable(coord)
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        self.wait(OrsEndpoint::Directions);
+        self.inner.get_directions(start, end)
+    }
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.inner.record_route(start, end, summary)
+    }
+}
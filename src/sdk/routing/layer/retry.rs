@@ -0,0 +1,43 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use crate::sdk::util::retry::RetryPolicy;
+
+/// Retries failed calls to the inner provider according to a `RetryPolicy`.
+pub struct RetryLayer<P> {
+    inner: P,
+    policy: RetryPolicy,
+}
+
+impl<P: RoutingProvider> RetryLayer<P> {
+    pub fn new(inner: P, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for RetryLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.policy
+            .run_routing("geocode", || self.inner.geocode(query))
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.policy
+            .run_routing("reverse_geocode", || self.inner.reverse_geocode(coord))
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.policy
+            .run_routing("is_routable", || self.inner.is_routable(coord))
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        self.policy
+            .run_routing("get_directions", || self.inner.get_directions(start, end))
+    }
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.inner.record_route(start, end, summary)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
+use crate::sdk::routing::route::RouteSummary;
+use crate::sdk::routing::service::RoutingProvider;
+use std::fmt::Debug;
+use std::time::Instant;
+
+/// Logs every request to the inner provider, and its outcome, at debug level; response
+/// bodies are logged at trace level. The providers themselves only log failures.
+pub struct TraceLayer<P> {
+    inner: P,
+    name: String,
+}
+
+impl<P: RoutingProvider> TraceLayer<P> {
+    /// `name` tags the log lines, e.g. "remote" or "local".
+    pub fn new(inner: P, name: impl Into<String>) -> Self {
+        Self {
+            inner,
+            name: name.into(),
+        }
+    }
+
+    fn trace<T: Debug>(
+        &self,
+        method: &str,
+        request: &dyn Debug,
+        call: impl FnOnce() -> Result<T, RoutingError>,
+    ) -> Result<T, RoutingError> {
+        log::debug!("[{}] {} request: {:?}", self.name, method, request);
+        let started = Instant::now();
+        let result = call();
+        match &result {
+            Ok(response) => {
+                log::debug!("[{}] {} ok in {:?}", self.name, method, started.elapsed());
+                log::trace!("[{}] {} response: {:?}", self.name, method, response);
+            }
+            Err(e) => log::debug!(
+                "[{}] {} failed in {:?}: {}",
+                self.name,
+                method,
+                started.elapsed(),
+                e
+            ),
+        }
+        result
+    }
+}
+
+impl<P: RoutingProvider> RoutingProvider for TraceLayer<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.trace("geocode", &query.text, || self.inner.geocode(query))
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        self.trace("reverse_geocode", &coord, || {
+            self.inner.reverse_geocode(coord)
+        })
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        self.trace("is_routable", &coord, || self.inner.is_routable(coord))
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        self.trace("get_directions", &(start, end), || {
+            self.inner.get_directions(start, end)
+        })
+    }
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        self.inner.record_route(start, end, summary)
+    }
+}
@@ -1,14 +1,25 @@
 pub mod cache;
+pub mod coverage;
 pub mod error;
 pub mod geocode;
+pub mod geojson;
+pub mod geometry;
+pub mod layer;
+pub mod origin;
+pub mod poly;
 pub mod provider;
 pub mod route;
 pub mod service;
+pub mod validate;
 
 pub use cache::{Coord, GeoCache};
-pub use error::RoutingError;
-pub use geocode::{find_routable_coordinates, get_or_cache_geocode};
+pub use coverage::{Coverage, CoverageStatus, EventCoverage, event_coverage};
+pub use error::{GeocodeError, RoutingError, RoutingErrorKind};
+pub use geocode::{DepartmentHint, GeocodeQuery, find_routable_coordinates, geocode_query};
+pub use layer::{LayerKind, ProviderStack};
+pub use origin::{Origin, ResolvedOrigin, resolve_origin};
 // This line works because providers/mod.rs re-exports them
 pub use provider::{HybridOrsProvider, LocalOrsProvider, RemoteOrsProvider};
 pub use route::{RouteSummary, get_road_distance};
 pub use service::RoutingProvider;
+pub use validate::{GeocodeFix, GeocodeValidation, SuspiciousGeocode, validate_geocodes};
@@ -0,0 +1,256 @@
+use super::cache::{Coord, GeoCache};
+use super::error::GeocodeError;
+use super::geocode::GeocodeQuery;
+use crate::sdk::departments::DepartmentLookup;
+use crate::sdk::error::ConfigError;
+use crate::sdk::util::retry::RetryPolicy;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::{fmt, str::FromStr};
+
+/// French government commune API, used to resolve postal and INSEE codes.
+const GEO_API_URL: &str = "https://geo.api.gouv.fr";
+
+/// The different ways a search origin can be given.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Origin {
+    /// A commune name and its department code, geocoded by the routing provider.
+    City { city: String, department: String },
+    /// Raw WGS84 coordinates.
+    Coordinates { lat: f64, lon: f64 },
+    /// A 5-digit French postal code; also gives the department.
+    PostalCode(String),
+    /// A 5-character INSEE commune code (e.g., "35238" or "2A004").
+    Insee(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::City { city, department } => write!(f, "{} ({})", city, department),
+            Origin::Coordinates { lat, lon } => write!(f, "{},{}", lat, lon),
+            Origin::PostalCode(code) => write!(f, "postal:{}", code),
+            Origin::Insee(code) => write!(f, "insee:{}", code),
+        }
+    }
+}
+
+impl FromStr for Origin {
+    type Err = ConfigError;
+
+    /// Parses `lat,lon`, `postal:35000`, `insee:35238`, or a bare 5-digit postal code.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(code) = s.strip_prefix("insee:") {
+            let code = code.trim().to_ascii_uppercase();
+            if code.len() != 5 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(ConfigError::InvalidOrigin(format!(
+                    "'{}': an INSEE code has 5 characters",
+                    code
+                )));
+            }
+            return Ok(Origin::Insee(code));
+        }
+        if let Some(code) = s.strip_prefix("postal:").or_else(|| s.strip_prefix("cp:")) {
+            let code = code.trim();
+            if !is_postal_code(code) {
+                return Err(ConfigError::InvalidOrigin(format!(
+                    "'{}': a postal code has 5 digits",
+                    code
+                )));
+            }
+            return Ok(Origin::PostalCode(code.to_string()));
+        }
+        if let Some((lat, lon)) = s.split_once(',') {
+            return parse_coordinates(lat, lon);
+        }
+        if is_postal_code(s) {
+            return Ok(Origin::PostalCode(s.to_string()));
+        }
+        Err(ConfigError::InvalidOrigin(format!(
+            "'{}': expected 'lat,lon', a postal code or 'insee:<code>'",
+            s
+        )))
+    }
+}
+
+fn is_postal_code(code: &str) -> bool {
+    code.len() == 5 && code.chars().all(|c| c.is_ascii_digit())
+}
+
+fn parse_coordinates(lat: &str, lon: &str) -> Result<Origin, ConfigError> {
+    let invalid = || ConfigError::InvalidOrigin(format!("invalid coordinates '{},{}'", lat, lon));
+    let lat: f64 = lat.trim().parse().map_err(|_| invalid())?;
+    let lon: f64 = lon.trim().parse().map_err(|_| invalid())?;
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(ConfigError::InvalidOrigin(format!(
+            "coordinates out of range: {},{}",
+            lat, lon
+        )));
+    }
+    Ok(Origin::Coordinates { lat, lon })
+}
+
+/// An origin ready to be routed from.
+#[derive(Debug, Clone)]
+pub struct ResolvedOrigin {
+    /// Human-readable name, also used for same-town matching (e.g., "Rennes").
+    pub label: String,
+    /// Geocode query, which is also the cache key for the origin's coordinate.
+    pub query: GeocodeQuery,
+    /// Department code, when known. For coordinates, it is the department whose outline
+    /// contains them, so it is only known with `[paths] boundaries`.
+    pub department: Option<String>,
+    /// Known coordinate; it is seeded into the cache so routing never re-geocodes it.
+    pub coord: Option<Coord>,
+}
+
+// --- Data Structures for parsing geo.api.gouv.fr responses ---
+
+#[derive(Deserialize)]
+struct Commune {
+    nom: String,
+    #[serde(rename = "codeDepartement")]
+    code_departement: String,
+    centre: Option<Point>,
+    #[serde(default)]
+    population: u64,
+}
+
+#[derive(Deserialize)]
+struct Point {
+    coordinates: [f64; 2],
+}
+
+/// Resolves an origin into a geocode query, seeding the cache with any known coordinate.
+/// Calls to the commune API are retried per `retry`.
+pub fn resolve_origin(
+    origin: &Origin,
+    lookup: &DepartmentLookup,
+    client: &Client,
+    retry: &RetryPolicy,
+    cache: &mut GeoCache,
+) -> Result<ResolvedOrigin, GeocodeError> {
+    match origin {
+        Origin::City { city, department } => {
+            let query = lookup
+                .build_geocode_query(city, department)
+                .ok_or_else(|| GeocodeError::UnknownDepartment(department.clone()))?;
+            Ok(ResolvedOrigin {
+                label: city.clone(),
+                query,
+                department: Some(department.clone()),
+                coord: None,
+            })
+        }
+        Origin::Coordinates { lat, lon } => {
+            let query = GeocodeQuery::new(format!("{:.6},{:.6}", lat, lon));
+            let coord = (*lon, *lat);
+            cache.insert_geocode(&query.text, coord);
+            let department = lookup.department_at(coord).map(str::to_string);
+            if department.is_none() {
+                log::warn!(
+                    "No department found for {}; scope filters and ferry rules treat it as mainland",
+                    query.text
+                );
+            }
+            Ok(ResolvedOrigin {
+                label: query.text.clone(),
+                query,
+                department,
+                coord: Some(coord),
+            })
+        }
+        Origin::PostalCode(code) => {
+            let department = lookup
+                .department_from_postal_code(code)
+                .ok_or_else(|| GeocodeError::InvalidPostalCode(code.clone()))?;
+            let url = format!(
+                "{}/communes?codePostal={}&fields=nom,codeDepartement,centre,population",
+                GEO_API_URL, code
+            );
+            let communes: Vec<Commune> = fetch_json(client, retry, &url)?;
+            // Several communes can share a postal code; the most populous is the usual intent.
+            let commune = communes
+                .into_iter()
+                .max_by_key(|c| c.population)
+                .ok_or_else(|| GeocodeError::NoResults(format!("postal code {}", code)))?;
+            if commune.code_departement != department {
+                log::warn!(
+                    "Postal code {} belongs to department {}, not {}",
+                    code,
+                    commune.code_departement,
+                    department
+                );
+            }
+            commune_origin(commune, lookup, cache)
+        }
+        Origin::Insee(code) => {
+            lookup
+                .department_from_insee(code)
+                .ok_or_else(|| GeocodeError::InvalidInseeCode(code.clone()))?;
+            let url = format!(
+                "{}/communes/{}?fields=nom,codeDepartement,centre",
+                GEO_API_URL, code
+            );
+            let commune: Commune = fetch_json(client, retry, &url)?;
+            commune_origin(commune, lookup, cache)
+        }
+    }
+}
+
+fn commune_origin(
+    commune: Commune,
+    lookup: &DepartmentLookup,
+    cache: &mut GeoCache,
+) -> Result<ResolvedOrigin, GeocodeError> {
+    let query = lookup
+        .build_geocode_query(&commune.nom, &commune.code_departement)
+        .ok_or_else(|| GeocodeError::UnknownDepartment(commune.code_departement.clone()))?;
+    let coord = commune.centre.map(|p| (p.coordinates[0], p.coordinates[1]));
+    if let Some(coord) = coord {
+        cache.insert_geocode(&query.text, coord);
+    }
+    log::debug!("Resolved commune {} to {:?}", commune.nom, coord);
+    Ok(ResolvedOrigin {
+        label: commune.nom,
+        query,
+        department: Some(commune.code_departement),
+        coord,
+    })
+}
+
+fn fetch_json<T: for<'de> Deserialize<'de>>(
+    client: &Client,
+    retry: &RetryPolicy,
+    url: &str,
+) -> Result<T, GeocodeError> {
+    log::debug!("Calling geo API: {}", url);
+    let text = retry.run(
+        url,
+        GeocodeError::is_retryable,
+        |_| None,
+        || {
+            let response = client.get(url).send()?;
+            let status = response.status();
+            let text = response.text()?;
+            if !status.is_success() {
+                return Err(GeocodeError::Service {
+                    url: url.to_string(),
+                    status,
+                    body: text,
+                });
+            }
+            Ok(text)
+        },
+    )?;
+    serde_json::from_str(&text).map_err(|e| {
+        log::error!(
+            "Failed to parse geo API response. URL: {}\nError: {}. Body: {}",
+            url,
+            e,
+            text
+        );
+        GeocodeError::Parse(e)
+    })
+}
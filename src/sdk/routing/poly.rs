@@ -0,0 +1,137 @@
+//! Parser for Osmosis `.poly` files, as used by `gen_west.sh` and `osmium extract`.
+//!
+//! ```text
+//! polygon          <- file name line
+//! 1                <- section name; "!2" would be a hole
+//!   -1.95  49.72   <- lon lat
+//! END              <- end of section
+//! END              <- end of file
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use super::geometry::{Area, Ring};
+use crate::sdk::error::ConfigError;
+
+/// Parses the contents of a `.poly` file.
+pub fn parse_poly(text: &str) -> Result<Area, String> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    let name = lines.next().ok_or("empty file")?.to_string();
+    let (mut outers, mut holes) = (Vec::new(), Vec::new());
+
+    loop {
+        let section = lines.next().ok_or("missing final END")?;
+        if section == "END" {
+            break;
+        }
+        let mut ring: Ring = Vec::new();
+        loop {
+            let line = lines
+                .next()
+                .ok_or_else(|| format!("section '{}' has no END", section))?;
+            if line == "END" {
+                break;
+            }
+            let mut parts = line.split_whitespace().map(str::parse::<f64>);
+            match (parts.next(), parts.next()) {
+                (Some(Ok(lon)), Some(Ok(lat))) => ring.push((lon, lat)),
+                _ => return Err(format!("invalid coordinate line '{}'", line)),
+            }
+        }
+        if section.starts_with('!') {
+            holes.push(ring);
+        } else {
+            outers.push(ring);
+        }
+    }
+
+    if outers.is_empty() {
+        return Err("no polygon sections".to_string());
+    }
+    Ok(Area::new(name, outers, holes))
+}
+
+/// Reads and parses a `.poly` file. The area is named after the file.
+pub fn load_poly<P: AsRef<Path>>(path: P) -> Result<Area, ConfigError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut area = parse_poly(&text).map_err(|message| ConfigError::Data {
+        path: path.to_path_buf(),
+        message,
+    })?;
+    if let Some(stem) = path.file_stem() {
+        area.name = stem.to_string_lossy().into_owned();
+    }
+    Ok(area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE_WITH_HOLE_AND_ISLAND: &str = "\
+test
+1
+  0.0 0.0
+  4.0 0.0
+  4.0 4.0
+  0.0 4.0
+END
+!2
+  1.0 1.0
+  2.0 1.0
+  2.0 2.0
+  1.0 2.0
+END
+3
+  10.0 10.0
+  11.0 10.0
+  11.0 11.0
+END
+END
+";
+
+    #[test]
+    fn parses_outer_rings_and_holes() {
+        let area = parse_poly(SQUARE_WITH_HOLE_AND_ISLAND).unwrap();
+        assert_eq!(area.name, "test");
+        assert_eq!(area.outers.len(), 2);
+        assert_eq!(area.holes.len(), 1);
+        assert_eq!(area.outers[0][1], (4.0, 0.0));
+        assert_eq!(area.outers[1].len(), 3);
+    }
+
+    #[test]
+    fn multi_ring_membership() {
+        let area = parse_poly(SQUARE_WITH_HOLE_AND_ISLAND).unwrap();
+        let cases = [
+            ((3.0, 3.0), true, "inside the first ring"),
+            ((1.5, 1.5), false, "inside the hole"),
+            ((10.5, 10.2), true, "inside the second ring"),
+            ((6.0, 6.0), false, "between the rings"),
+            ((4.0, 2.0), true, "on an outer edge"),
+            ((0.0, 0.0), true, "on a vertex"),
+        ];
+        for (point, expected, case) in cases {
+            assert_eq!(area.contains(point), expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        let cases = [
+            ("", "empty file"),
+            ("name\n1\n 0 0\nEND\n", "missing final END"),
+            ("name\n1\n 0 0\n", "section without END"),
+            ("name\n1\n 0 north\nEND\nEND\n", "invalid coordinate"),
+            ("name\n!1\n 0 0\nEND\nEND\n", "only holes"),
+        ];
+        for (text, case) in cases {
+            assert!(parse_poly(text).is_err(), "{}", case);
+        }
+    }
+}
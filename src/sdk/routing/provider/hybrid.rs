@@ -1,15 +1,23 @@
 use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::coverage::Coverage;
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
 use crate::sdk::routing::route::RouteSummary;
 use crate::sdk::routing::service::RoutingProvider;
+use crate::sdk::util::quota::QuotaTracker;
 use crate::sdk::util::rate_limit::Limiter;
-use std::error::Error;
+use crate::sdk::util::retry::RetryPolicy;
+use std::sync::Arc;
 
 use super::local::LocalOrsProvider;
 use super::remote::RemoteOrsProvider;
 
+/// Geocodes on the remote API and routes on the local server. With a coverage, routes
+/// leaving the extract the local graph was built from go to the remote API instead.
 pub struct HybridOrsProvider {
     remote: RemoteOrsProvider,
     local: LocalOrsProvider,
+    coverage: Option<Coverage>,
 }
 
 impl HybridOrsProvider {
@@ -17,28 +25,70 @@ impl HybridOrsProvider {
         Self {
             remote: RemoteOrsProvider::new(api_key, limiter),
             local: LocalOrsProvider::new(local_base_url),
+            coverage: None,
         }
     }
+
+    /// Only routes on the local server when both ends lie inside `coverage`.
+    pub fn with_coverage(mut self, coverage: Coverage) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
+    /// Sets the ORS routing profile used for directions (default "driving-car").
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        let profile = profile.into();
+        self.remote = self.remote.with_profile(profile.clone());
+        self.local = self.local.with_profile(profile);
+        self
+    }
+
+    /// Drops the remote provider's built-in limiter, for stacks with a `RateLimitLayer`.
+    pub fn without_rate_limit(mut self) -> Self {
+        self.remote = self.remote.without_rate_limit();
+        self
+    }
+
+    /// Tracks the daily quota of the remote API; local calls are not metered.
+    pub fn with_quota(mut self, quota: Arc<QuotaTracker>) -> Self {
+        self.remote = self.remote.with_quota(quota);
+        self
+    }
+
+    /// Sets the retry policy used by both the remote and the local server.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.remote = self.remote.with_retry(retry.clone());
+        self.local = self.local.with_retry(retry);
+        self
+    }
 }
 
 impl RoutingProvider for HybridOrsProvider {
-    fn geocode(&self, city: &str) -> Result<Coord, Box<dyn Error>> {
-        log::debug!("[Hybrid Provider] Using REMOTE for geocode");
-        self.remote.geocode(city)
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        self.remote.geocode(query)
     }
 
-    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, Box<dyn Error>> {
-        log::debug!("[Hybrid Provider] Using REMOTE for reverse_geocode");
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
         self.remote.reverse_geocode(coord)
     }
 
-    fn is_routable(&self, coord: Coord) -> Result<bool, Box<dyn Error>> {
-        log::debug!("[Hybrid Provider] Using LOCAL for is_routable");
-        self.local.is_routable(coord)
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        if self.coverage.as_ref().is_none_or(|c| c.contains(coord)) {
+            self.local.is_routable(coord)
+        } else {
+            self.remote.is_routable(coord)
+        }
     }
 
-    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, Box<dyn Error>> {
-        log::debug!("[Hybrid Provider] Using LOCAL for get_directions");
-        self.local.get_directions(start, end)
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        if self
+            .coverage
+            .as_ref()
+            .is_none_or(|c| c.covers_route(start, end))
+        {
+            self.local.get_directions(start, end)
+        } else {
+            self.remote.get_directions(start, end)
+        }
     }
 }
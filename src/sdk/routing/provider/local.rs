@@ -1,16 +1,23 @@
+use super::pelias::{search_params, select_feature};
+use super::status::OrsStatus;
 use super::types::{DirectionsResponse, GeoResponse};
 use crate::sdk::routing::cache::Coord;
-use crate::sdk::routing::error::{OrsErrorPayload, RoutingError};
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
 use crate::sdk::routing::route::RouteSummary;
 use crate::sdk::routing::service::RoutingProvider;
-use reqwest::blocking::Client;
-use serde_json::json;
-use std::error::Error;
-use std::time::Duration;
+use crate::sdk::util::retry::RetryPolicy;
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, RequestBuilder};
+use serde_json::{Value, json};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub struct LocalOrsProvider {
     client: Client,
     base_url: String,
+    profile: String,
+    retry: RetryPolicy,
 }
 
 impl LocalOrsProvider {
@@ -18,20 +25,141 @@ impl LocalOrsProvider {
         Self {
             client: Client::builder()
                 .timeout(Duration::from_secs(15))
+                // A stopped container refuses or drops connections; don't wait for the full timeout.
+                .connect_timeout(Duration::from_secs(3))
                 .build()
                 .unwrap(),
             base_url,
+            profile: "driving-car".to_string(),
+            retry: RetryPolicy::default(),
         }
     }
-}
 
-impl RoutingProvider for LocalOrsProvider {
-    fn geocode(&self, city: &str) -> Result<Coord, Box<dyn Error>> {
-        log::debug!("[PROVIDER] Calling local geocode for city: \"{}\"", city);
-        let url = format!("{}/pelias/v1/search?text={}", self.base_url, city);
+    /// Sets the retry policy for failed calls (default: 3 attempts on transient errors).
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
 
+    /// Sets the ORS routing profile used for directions (default "driving-car").
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Whether the instance has finished building its graphs (`/v2/health`).
+    pub fn is_ready(&self) -> Result<bool, RoutingError> {
+        let url = format!("{}/v2/health", self.base_url);
         let response = self.client.get(&url).send()?;
+        let status = response.status();
+        // ORS answers 503 {"status":"not ready"} while the graphs are being built.
+        if status == StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(false);
+        }
+        let headers = response.headers().clone();
         let text = response.text()?;
+        if !status.is_success() {
+            return Err(RoutingError::from_response(status, &headers, text));
+        }
+        let body: Value = serde_json::from_str(&text)?;
+        Ok(body.get("status").and_then(Value::as_str) == Some("ready"))
+    }
+
+    /// Reads `/v2/status`: engine version, enabled profiles and OSM data dates.
+    /// Only the health check is done while the instance is not ready.
+    pub fn status(&self) -> Result<OrsStatus, RoutingError> {
+        if !self.is_ready()? {
+            return Ok(OrsStatus::default());
+        }
+        let url = format!("{}/v2/status", self.base_url);
+        let text = self.execute("local status", || self.client.get(&url))?;
+        let body: Value = serde_json::from_str(&text)?;
+        Ok(OrsStatus {
+            ready: true,
+            ..OrsStatus::from_status_json(&body)
+        })
+    }
+
+    /// Checks that the instance is ready and serves the configured profile.
+    pub fn check_ready(&self) -> Result<OrsStatus, RoutingError> {
+        let status = self.status()?;
+        if !status.ready {
+            return Err(RoutingError::NotReady {
+                url: self.base_url.clone(),
+            });
+        }
+        if !status.profiles.is_empty() && !status.has_profile(&self.profile) {
+            return Err(RoutingError::ProfileUnavailable {
+                profile: self.profile.clone(),
+                available: status.profile_names(),
+            });
+        }
+        Ok(status)
+    }
+
+    /// Polls `/v2/health` every `interval` until the instance is ready, then checks it
+    /// like `check_ready`. Connection failures count as "not ready", since the container
+    /// may still be starting.
+    pub fn wait_until_ready(
+        &self,
+        timeout: Duration,
+        interval: Duration,
+    ) -> Result<OrsStatus, RoutingError> {
+        let started = Instant::now();
+        loop {
+            match self.is_ready() {
+                Ok(true) => return self.check_ready(),
+                Ok(false) => log::info!(
+                    "ORS at {} is building its graphs ({}s elapsed)...",
+                    self.base_url,
+                    started.elapsed().as_secs()
+                ),
+                Err(e) => log::info!(
+                    "ORS at {} is not reachable yet ({}s elapsed): {}",
+                    self.base_url,
+                    started.elapsed().as_secs(),
+                    e
+                ),
+            }
+            if started.elapsed() >= timeout {
+                return Err(RoutingError::NotReady {
+                    url: self.base_url.clone(),
+                });
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    /// Sends a request built by `build`, retrying per the policy.
+    fn execute(
+        &self,
+        what: &str,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<String, RoutingError> {
+        self.retry.run_routing(what, || {
+            let response = build().send()?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let text = response.text()?;
+            if status.is_success() {
+                Ok(text)
+            } else {
+                Err(RoutingError::from_response(status, &headers, text))
+            }
+        })
+    }
+}
+
+impl RoutingProvider for LocalOrsProvider {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        let url = format!(
+            "{}/pelias/v1/search?text={}&{}",
+            self.base_url,
+            query.text,
+            search_params(query)
+        );
+
+        let text = self.execute("local geocode", || self.client.get(&url))?;
 
         let resp: GeoResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
@@ -43,27 +171,16 @@ impl RoutingProvider for LocalOrsProvider {
             e
         })?;
 
-        let coords = resp
-            .features
-            .first()
-            .ok_or_else(|| RoutingError::Generic(format!("No geocode results for city: {}", city)))?
-            .geometry
-            .coordinates;
-        Ok((coords[0], coords[1]))
+        Ok(select_feature(query, &resp)?)
     }
 
-    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, Box<dyn Error>> {
-        log::debug!(
-            "[PROVIDER] Calling local reverse_geocode for coord: {:?}",
-            coord
-        );
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
         let url = format!(
             "{}/pelias/v1/reverse?point.lon={}&point.lat={}",
             self.base_url, coord.0, coord.1
         );
 
-        let response = self.client.get(&url).send()?;
-        let text = response.text()?;
+        let text = self.execute("local reverse_geocode", || self.client.get(&url))?;
 
         let body: GeoResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
@@ -82,19 +199,23 @@ impl RoutingProvider for LocalOrsProvider {
             .collect())
     }
 
-    fn is_routable(&self, coord: Coord) -> Result<bool, Box<dyn Error>> {
-        log::debug!(
-            "[PROVIDER] Calling local is_routable for coord: {:?}",
-            coord
-        );
-        let url = format!("{}/v2/directions/driving-car", self.base_url);
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        let url = format!("{}/v2/directions/{}", self.base_url, self.profile);
         let body = json!({ "coordinates": [[coord.0, coord.1], [coord.0, coord.1]] });
 
-        let response = self.client.post(url).json(&body).send()?;
-        Ok(response.status().is_success())
+        match self.execute("local is_routable", || self.client.post(&url).json(&body)) {
+            Ok(_) => Ok(true),
+            // Transport and server failures say nothing about the point itself.
+            Err(
+                e @ (RoutingError::RequestError(_)
+                | RoutingError::RateLimited { .. }
+                | RoutingError::ServerError { .. }),
+            ) => Err(e),
+            Err(_) => Ok(false),
+        }
     }
 
-    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, Box<dyn Error>> {
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
         if start == end {
             return Ok(RouteSummary {
                 distance_km: 0.0,
@@ -102,46 +223,27 @@ impl RoutingProvider for LocalOrsProvider {
             });
         }
 
-        log::debug!(
-            "[PROVIDER] Calling local get_directions for {:?} -> {:?}",
-            start,
-            end
-        );
-        let url = format!("{}/v2/directions/driving-car", self.base_url);
+        let url = format!("{}/v2/directions/{}", self.base_url, self.profile);
         let body = json!({ "coordinates": [[start.0, start.1], [end.0, end.1]] });
 
-        let response = match self.client.post(&url).json(&body).send() {
-            Ok(resp) => resp,
-            Err(e) => {
-                log::error!(
+        let text = self
+            .execute("local get_directions", || {
+                self.client.post(&url).json(&body)
+            })
+            .inspect_err(|e| match e {
+                RoutingError::RequestError(_) => log::error!(
                     "Failed to send POST request to local ORS. URL: {}\nError: {}",
                     url,
                     e
-                );
-                return Err(Box::new(e));
-            }
-        };
-
-        let status = response.status();
-        let text = response.text()?;
-
-        if !status.is_success() {
-            // Try to parse the structured error first
-            if let Ok(payload) = serde_json::from_str::<OrsErrorPayload>(&text) {
-                return Err(Box::new(RoutingError::ApiError {
-                    code: payload.error.code,
-                    message: payload.error.message,
-                }));
-            } else {
-                // Fallback to a raw error if parsing fails
-                log::error!(
-                    "API returned non-success status: {}. Unparseable Body: {}",
-                    status,
-                    text
-                );
-                return Err(Box::new(RoutingError::RawApiError(text)));
-            }
-        }
+                ),
+                RoutingError::RawApiError(text) => {
+                    log::error!(
+                        "API returned non-success status. Unparseable Body: {}",
+                        text
+                    )
+                }
+                _ => {}
+            })?;
 
         let route_response: DirectionsResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
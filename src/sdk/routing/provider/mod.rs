@@ -1,8 +1,11 @@
 pub mod hybrid;
 pub mod local;
+mod pelias;
 pub mod remote;
+pub mod status;
 pub mod types;
 
 pub use hybrid::HybridOrsProvider;
 pub use local::LocalOrsProvider;
 pub use remote::RemoteOrsProvider;
+pub use status::{OrsProfileStatus, OrsStatus};
@@ -0,0 +1,108 @@
+//! Builds Pelias searches and picks the right result out of their responses.
+//!
+//! With dozens of communes sharing a name, `features.first()` is often in the wrong
+//! department, so the search is narrowed to the query's department and every result
+//! is checked against it.
+
+use super::types::{Feature, GeoResponse};
+use crate::sdk::departments::normalize_name;
+use crate::sdk::routing::cache::Coord;
+use crate::sdk::routing::error::GeocodeError;
+use crate::sdk::routing::geocode::{DepartmentHint, GeocodeQuery};
+
+/// Results below this confidence are accepted but logged as doubtful.
+const LOW_CONFIDENCE: f64 = 0.6;
+
+/// Query parameters for a search: restricted to France, focused on the department's
+/// centre and bounded by its outline's bounding box when they are known.
+pub(crate) fn search_params(query: &GeocodeQuery) -> String {
+    let mut params = String::from("boundary.country=FR&size=10");
+    let Some(department) = &query.department else {
+        return params;
+    };
+    if let Some((lon, lat)) = department.centroid {
+        params.push_str(&format!("&focus.point.lat={}&focus.point.lon={}", lat, lon));
+    }
+    if let Some(bbox) = department.bbox {
+        params.push_str(&format!(
+            "&boundary.rect.min_lat={}&boundary.rect.min_lon={}&boundary.rect.max_lat={}&boundary.rect.max_lon={}",
+            bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon
+        ));
+    }
+    params
+}
+
+/// Preference order of Pelias layers: communes before districts, streets or venues.
+fn layer_rank(layer: Option<&str>) -> u8 {
+    match layer {
+        Some("locality") => 0,
+        Some("localadmin") => 1,
+        Some("borough") | Some("neighbourhood") => 2,
+        Some("county") => 3,
+        _ => 4,
+    }
+}
+
+/// Whether Pelias places the feature in the department (its `region` in France).
+fn in_department(feature: &Feature, department: &DepartmentHint) -> bool {
+    feature
+        .properties
+        .region
+        .as_deref()
+        .is_some_and(|region| normalize_name(region) == normalize_name(&department.name))
+}
+
+/// Chooses the best feature for `query`: only results in its department when it has one,
+/// preferring communes over other layers, then higher confidence.
+pub(crate) fn select_feature(
+    query: &GeocodeQuery,
+    response: &GeoResponse,
+) -> Result<Coord, GeocodeError> {
+    let features = &response.features;
+    if features.is_empty() {
+        return Err(GeocodeError::NoResults(query.text.clone()));
+    }
+    let candidates: Vec<&Feature> = match &query.department {
+        Some(department) => {
+            let matching: Vec<&Feature> = features
+                .iter()
+                .filter(|f| in_department(f, department))
+                .collect();
+            if matching.is_empty() {
+                return Err(GeocodeError::DepartmentMismatch {
+                    query: query.text.clone(),
+                    expected: department.name.clone(),
+                    found: features
+                        .iter()
+                        .filter_map(|f| f.properties.label.clone())
+                        .collect(),
+                });
+            }
+            matching
+        }
+        None => features.iter().collect(),
+    };
+    let best = candidates
+        .into_iter()
+        .min_by(|a, b| {
+            let rank = |f: &Feature| layer_rank(f.properties.layer.as_deref());
+            let confidence = |f: &Feature| f.properties.confidence.unwrap_or(0.0);
+            rank(a)
+                .cmp(&rank(b))
+                .then(confidence(b).total_cmp(&confidence(a)))
+        })
+        .expect("candidates is not empty");
+
+    let properties = &best.properties;
+    if properties.confidence.is_some_and(|c| c < LOW_CONFIDENCE) {
+        log::warn!(
+            "Low-confidence geocode for '{}': {} ({}, confidence {:.2})",
+            query,
+            properties.label.as_deref().unwrap_or("?"),
+            properties.layer.as_deref().unwrap_or("?"),
+            properties.confidence.unwrap_or_default()
+        );
+    }
+    let coords = best.geometry.coordinates;
+    Ok((coords[0], coords[1]))
+}
@@ -1,19 +1,32 @@
+use super::pelias::{search_params, select_feature};
 use super::types::{DirectionsResponse, GeoResponse};
 use crate::sdk::routing::cache::Coord;
-use crate::sdk::routing::error::{OrsErrorPayload, RoutingError};
+use crate::sdk::routing::error::RoutingError;
+use crate::sdk::routing::geocode::GeocodeQuery;
 use crate::sdk::routing::route::RouteSummary;
 use crate::sdk::routing::service::RoutingProvider;
-use crate::sdk::util::rate_limit::Limiter;
-use reqwest::blocking::Client;
+use crate::sdk::util::quota::{OrsEndpoint, QuotaTracker};
+use crate::sdk::util::rate_limit::{LimitKey, Limiter};
+use crate::sdk::util::retry::{RateLimitInfo, RetryPolicy};
+use reqwest::blocking::{Client, RequestBuilder};
 use serde_json::json;
-use std::error::Error;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub struct RemoteOrsProvider {
     client: Client,
     api_key: String,
     base_url: String,
-    limiter: Limiter,
+    /// Host of `base_url`, used to key the rate limiter.
+    host: String,
+    profile: String,
+    /// `None` when rate limiting is left to a `RateLimitLayer`.
+    limiter: Option<Limiter>,
+    retry: RetryPolicy,
+    quota: Option<Arc<QuotaTracker>>,
+    /// Set when ORS reports the rate-limit window as used up.
+    paused_until: Mutex<Option<Instant>>,
 }
 
 impl RemoteOrsProvider {
@@ -25,22 +38,110 @@ impl RemoteOrsProvider {
                 .unwrap(),
             api_key,
             base_url: "https://api.openrouteservice.org".to_string(),
-            limiter,
+            host: "api.openrouteservice.org".to_string(),
+            profile: "driving-car".to_string(),
+            limiter: Some(limiter),
+            retry: RetryPolicy::default(),
+            quota: None,
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    /// Sets the retry policy for failed calls (default: 3 attempts on transient errors).
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Drops the built-in limiter, for stacks that add a `RateLimitLayer` instead.
+    pub fn without_rate_limit(mut self) -> Self {
+        self.limiter = None;
+        self
+    }
+
+    /// Counts calls against the daily quotas, refusing them once the quota is used up.
+    /// The tracker can be shared by several providers using the same API key.
+    pub fn with_quota(mut self, quota: Arc<QuotaTracker>) -> Self {
+        self.quota = Some(quota);
+        self
+    }
+
+    /// Sets the ORS routing profile used for directions (default "driving-car").
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Sends a request built by `build`, retrying per the policy. Each attempt is counted
+    /// against the daily quota, and waits for the rate limiter and for any pause requested
+    /// by the ORS rate-limit headers.
+    fn execute(
+        &self,
+        endpoint: OrsEndpoint,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<String, RoutingError> {
+        self.retry.run_routing(&format!("ORS {}", endpoint), || {
+            if let Some(quota) = &self.quota {
+                quota.acquire(endpoint)?;
+            }
+            self.wait_for_reset();
+            if let Some(limiter) = &self.limiter {
+                limiter.wait(&LimitKey::new(self.host.as_str(), endpoint.to_string()));
+            }
+            let response = build().send()?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            self.record_rate_limit(endpoint, &RateLimitInfo::from_headers(&headers));
+            let text = response.text()?;
+            if status.is_success() {
+                return Ok(text);
+            }
+            let error = RoutingError::from_response(status, &headers, text);
+            if let (RoutingError::QuotaExceeded { .. }, Some(quota)) = (&error, &self.quota) {
+                quota.sync(endpoint, 0, None);
+            }
+            Err(error)
+        })
+    }
+
+    fn wait_for_reset(&self) {
+        let paused_until = self.paused_until.lock().unwrap().take();
+        if let Some(delay) =
+            paused_until.and_then(|until| until.checked_duration_since(Instant::now()))
+        {
+            log::info!("ORS rate-limit window used up, waiting {:?}", delay);
+            thread::sleep(delay);
+        }
+    }
+
+    fn record_rate_limit(&self, endpoint: OrsEndpoint, info: &RateLimitInfo) {
+        if let Some(remaining) = info.remaining {
+            log::trace!("ORS {} calls remaining in window: {}", endpoint, remaining);
+            if let Some(quota) = &self.quota {
+                quota.sync(endpoint, remaining, info.reset_in);
+            }
+        }
+        // Short windows are waited out; a daily reset is left to the quota tracker.
+        if info.remaining == Some(0)
+            && let Some(reset_in) = info.reset_in
+            && reset_in <= Duration::from_millis(self.retry.max_delay_ms)
+        {
+            *self.paused_until.lock().unwrap() = Some(Instant::now() + reset_in);
         }
     }
 }
 
 impl RoutingProvider for RemoteOrsProvider {
-    fn geocode(&self, city: &str) -> Result<Coord, Box<dyn Error>> {
-        self.limiter.wait();
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
         let url = format!(
-            "{}/geocode/search?api_key={}&text={}",
-            self.base_url, self.api_key, city
+            "{}/geocode/search?api_key={}&text={}&{}",
+            self.base_url,
+            self.api_key,
+            query.text,
+            search_params(query)
         );
-        log::debug!("[PROVIDER] Calling remote geocode for city: \"{}\"", city);
 
-        let response = self.client.get(&url).send()?;
-        let text = response.text()?;
+        let text = self.execute(OrsEndpoint::Geocode, || self.client.get(&url))?;
 
         let resp: GeoResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
@@ -52,28 +153,16 @@ impl RoutingProvider for RemoteOrsProvider {
             e
         })?;
 
-        let coords = resp
-            .features
-            .first()
-            .ok_or_else(|| RoutingError::Generic(format!("No geocode results for city: {}", city)))?
-            .geometry
-            .coordinates;
-        Ok((coords[0], coords[1]))
+        Ok(select_feature(query, &resp)?)
     }
 
-    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, Box<dyn Error>> {
-        self.limiter.wait();
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
         let url = format!(
             "{}/geocode/reverse?point.lon={}&point.lat={}&api_key={}",
             self.base_url, coord.0, coord.1, self.api_key
         );
-        log::debug!(
-            "[PROVIDER] Calling remote reverse_geocode for coord: {:?}",
-            coord
-        );
 
-        let response = self.client.get(&url).send()?;
-        let text = response.text()?;
+        let text = self.execute(OrsEndpoint::ReverseGeocode, || self.client.get(&url))?;
 
         let body: GeoResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
@@ -92,25 +181,32 @@ impl RoutingProvider for RemoteOrsProvider {
             .collect())
     }
 
-    fn is_routable(&self, coord: Coord) -> Result<bool, Box<dyn Error>> {
-        self.limiter.wait();
-        log::debug!(
-            "[PROVIDER] Calling remote is_routable for coord: {:?}",
-            coord
-        );
-        let url = format!("{}/v2/directions/driving-car", self.base_url);
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        let url = format!("{}/v2/directions/{}", self.base_url, self.profile);
         let body = json!({ "coordinates": [[coord.0, coord.1], [coord.0, coord.1]] });
 
-        let response = self
-            .client
-            .post(url)
-            .header("Authorization", &self.api_key)
-            .json(&body)
-            .send()?;
-        Ok(response.status().is_success())
+        let result = self.execute(OrsEndpoint::Directions, || {
+            self.client
+                .post(&url)
+                .header("Authorization", &self.api_key)
+                .json(&body)
+        });
+        match result {
+            Ok(_) => Ok(true),
+            // Transport and throttling failures say nothing about the point itself.
+            Err(
+                e @ (RoutingError::RequestError(_)
+                | RoutingError::RateLimited { .. }
+                | RoutingError::QuotaExceeded { .. }
+                | RoutingError::Unauthorized { .. }
+                | RoutingError::DailyQuotaExhausted { .. }
+                | RoutingError::ServerError { .. }),
+            ) => Err(e),
+            Err(_) => Ok(false),
+        }
     }
 
-    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, Box<dyn Error>> {
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
         if start == end {
             return Ok(RouteSummary {
                 distance_km: 0.0,
@@ -118,54 +214,31 @@ impl RoutingProvider for RemoteOrsProvider {
             });
         }
 
-        self.limiter.wait();
-        log::debug!(
-            "[PROVIDER] Calling remote get_directions for {:?} -> {:?}",
-            start,
-            end
-        );
-        let url = format!("{}/v2/directions/driving-car", self.base_url);
+        let url = format!("{}/v2/directions/{}", self.base_url, self.profile);
         let body = json!({ "coordinates": [[start.0, start.1], [end.0, end.1]] });
 
-        let response = match self
-            .client
-            .post(&url)
-            .header("Authorization", &self.api_key)
-            .json(&body)
-            .send()
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                log::error!(
+        let text = self
+            .execute(OrsEndpoint::Directions, || {
+                self.client
+                    .post(&url)
+                    .header("Authorization", &self.api_key)
+                    .json(&body)
+            })
+            .inspect_err(|e| match e {
+                RoutingError::RequestError(_) => log::error!(
                     "Failed to send POST request. URL: {}\nBody: {}\nError: {}",
                     url,
                     serde_json::to_string_pretty(&body).unwrap_or_default(),
                     e
-                );
-                return Err(Box::new(e));
-            }
-        };
-
-        let status = response.status();
-        let text = response.text()?;
-
-        if !status.is_success() {
-            // Try to parse the structured error first
-            if let Ok(payload) = serde_json::from_str::<OrsErrorPayload>(&text) {
-                return Err(Box::new(RoutingError::ApiError {
-                    code: payload.error.code,
-                    message: payload.error.message,
-                }));
-            } else {
-                // Fallback to a raw error if parsing fails
-                log::error!(
-                    "API returned non-success status: {}. Unparseable Body: {}",
-                    status,
-                    text
-                );
-                return Err(Box::new(RoutingError::RawApiError(text)));
-            }
-        }
+                ),
+                RoutingError::RawApiError(text) => {
+                    log::error!(
+                        "API returned non-success status. Unparseable Body: {}",
+                        text
+                    )
+                }
+                _ => {}
+            })?;
 
         let route_response: DirectionsResponse = serde_json::from_str(&text).map_err(|e| {
             log::error!(
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// One routing profile loaded by an ORS instance.
+#[derive(Debug, Clone, Serialize)]
+pub struct OrsProfileStatus {
+    /// API name of the profile, e.g. "driving-car".
+    pub name: String,
+    /// Date of the OSM extract the graph was built from.
+    pub osm_date: Option<String>,
+    pub graph_date: Option<String>,
+}
+
+/// What `/v2/health` and `/v2/status` report about an ORS instance.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrsStatus {
+    /// Whether the graphs are built and requests can be served.
+    pub ready: bool,
+    pub engine_version: Option<String>,
+    pub profiles: Vec<OrsProfileStatus>,
+    /// Enabled services, e.g. "routing", "matrix", "isochrones".
+    pub services: Vec<String>,
+}
+
+impl OrsStatus {
+    /// Parses the body of `/v2/status`. Both the ORS 8 layout (profiles keyed by name,
+    /// `encoder_name`, `graph_build_date`) and the older one (`"profile 1": { "profiles":
+    /// ..., "creation_date": ... }`) are understood.
+    pub fn from_status_json(body: &Value) -> Self {
+        let text = |value: &Value, keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| value.get(*key).and_then(Value::as_str))
+                .map(str::to_string)
+        };
+        let profiles = body
+            .get("profiles")
+            .and_then(Value::as_object)
+            .map(|profiles| {
+                profiles
+                    .iter()
+                    .map(|(key, profile)| OrsProfileStatus {
+                        name: text(profile, &["encoder_name", "profiles"])
+                            .unwrap_or_else(|| key.clone()),
+                        osm_date: text(profile, &["osm_date"]),
+                        graph_date: text(profile, &["graph_build_date", "creation_date"]),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let services = body
+            .get("services")
+            .and_then(Value::as_array)
+            .map(|services| {
+                services
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            ready: false,
+            engine_version: body.get("engine").and_then(|e| text(e, &["version"])),
+            profiles,
+            services,
+        }
+    }
+
+    pub fn has_profile(&self, profile: &str) -> bool {
+        self.profiles.iter().any(|p| p.name == profile)
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.iter().map(|p| p.name.clone()).collect()
+    }
+}
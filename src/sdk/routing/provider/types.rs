@@ -9,6 +9,23 @@ pub struct GeoResponse {
 #[derive(Deserialize)]
 pub struct Feature {
     pub geometry: Geometry,
+    #[serde(default)]
+    pub properties: FeatureProperties,
+}
+/// Pelias properties of a geocoding result. For France, `region` is the department
+/// and `macroregion` the administrative region.
+#[derive(Deserialize, Default, Debug)]
+pub struct FeatureProperties {
+    pub label: Option<String>,
+    /// Kind of place: "locality", "localadmin", "street", "venue"...
+    pub layer: Option<String>,
+    /// Between 0 and 1: how well the result matches the query text.
+    pub confidence: Option<f64>,
+    pub locality: Option<String>,
+    pub region: Option<String>,
+    pub macroregion: Option<String>,
+    pub postalcode: Option<String>,
+    pub country_a: Option<String>,
 }
 #[derive(Deserialize)]
 pub struct Geometry {
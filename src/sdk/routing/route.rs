@@ -1,9 +1,7 @@
-use super::cache::{CityPairKey, GeoCache};
 use super::error::RoutingError;
-use super::geocode::{find_routable_coordinates, get_or_cache_geocode};
+use super::geocode::{GeocodeQuery, find_routable_coordinates, geocode_query};
 use super::service::RoutingProvider;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct RouteSummary {
@@ -11,49 +9,56 @@ pub struct RouteSummary {
     pub duration_hours: f64,
 }
 
-/// Calculates road distance, handling caching and retrying with routable coordinates if necessary.
+/// Calculates road distance from `city1` to `city2`, retrying with routable coordinates
+/// if necessary. The way back is a separate route, as one-way streets and ferries can
+/// make it differ.
+///
+/// Geocodes and directions are cached by the provider's cache layer, if it has one; a
+/// route found between snapped points is recorded under the geocoded ones.
 pub fn get_road_distance(
-    city1: &str,
-    city2: &str,
+    city1: &GeocodeQuery,
+    city2: &GeocodeQuery,
     provider: &dyn RoutingProvider,
-    cache: &mut GeoCache,
-) -> Result<RouteSummary, Box<dyn Error>> {
-    let key = CityPairKey::new(city1, city2);
-    if let Some(summary) = cache.get_route(&key) {
-        log::debug!("[CACHE HIT] Route {} -> {}", city1, city2);
-        return Ok(summary);
-    }
-    log::debug!("[CACHE MISS] Route {} -> {}", city1, city2);
-
-    let mut coord1 = get_or_cache_geocode(city1, provider, cache)?;
-    let mut coord2 = get_or_cache_geocode(city2, provider, cache)?;
+) -> Result<RouteSummary, RoutingError> {
+    let start = geocode_query(city1, provider)?;
+    let end = geocode_query(city2, provider)?;
 
-    match provider.get_directions(coord1, coord2) {
-        Ok(summary) => {
-            cache.insert_route(key, summary);
+    match provider.get_directions(start, end) {
+        Err(RoutingError::UnroutablePoint | RoutingError::PointNotFound { .. }) => {
+            log::warn!(
+                "Unroutable point for {} -> {}. Finding snapped coordinates.",
+                city1,
+                city2
+            );
+            let snapped_start = find_routable_coordinates(start.0, start.1, provider)?;
+            let snapped_end = find_routable_coordinates(end.0, end.1, provider)?;
+            log::info!(
+                "Retrying with new coordinates: {:?} -> {:?}",
+                snapped_start,
+                snapped_end
+            );
+            let summary = provider.get_directions(snapped_start, snapped_end)?;
+            provider.record_route(start, end, summary);
             Ok(summary)
         }
-        Err(e) => {
-            if let Some(RoutingError::UnroutablePoint) = e.downcast_ref::<RoutingError>() {
-                log::warn!(
-                    "Unroutable point for {} -> {}. Finding snapped coordinates.",
-                    city1,
-                    city2
-                );
-                coord1 = find_routable_coordinates(coord1.0, coord1.1, provider)?;
-                coord2 = find_routable_coordinates(coord2.0, coord2.1, provider)?;
-                log::info!(
-                    "Retrying with new coordinates: {:?} -> {:?}",
-                    coord1,
-                    coord2
-                );
+        result => result,
+    }
+}
 
-                let summary = provider.get_directions(coord1, coord2)?;
-                cache.insert_route(key, summary);
-                Ok(summary)
-            } else {
-                Err(e)
-            }
+/// Logs a failed route lookup, treating "route too long" as expected.
+pub(crate) fn log_routing_failure(from: &GeocodeQuery, to: &GeocodeQuery, e: &RoutingError) {
+    match e {
+        RoutingError::RouteTooLong { .. } => {
+            log::debug!(
+                "[EXPECTED LIMIT] Route {} -> {} is too long: {}",
+                from,
+                to,
+                e
+            );
+        }
+        _ if e.is_quota_exhausted() => {
+            log::warn!("Stopping early, results are partial: {}", e);
         }
+        _ => log::error!("Routing error for '{}' to '{}': {}", from, to, e),
     }
 }
@@ -1,17 +1,44 @@
 use super::cache::Coord;
+use super::error::RoutingError;
+use super::geocode::GeocodeQuery;
 use super::route::RouteSummary;
-use std::error::Error;
 
 pub trait RoutingProvider: Send + Sync {
-    /// Geocodes a city name to a coordinate.
-    fn geocode(&self, city: &str) -> Result<Coord, Box<dyn Error>>;
+    /// Geocodes a place to a coordinate, inside its department when the query names one.
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError>;
 
     /// Finds potential coordinates near a given point.
-    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, Box<dyn Error>>;
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError>;
 
     /// Checks if a specific coordinate is on the routable road network.
-    fn is_routable(&self, coord: Coord) -> Result<bool, Box<dyn Error>>;
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError>;
 
     /// Gets directions between two points.
-    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, Box<dyn Error>>;
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError>;
+
+    /// Notes that `summary` is the route from `start` to `end`, found by routing between
+    /// nearby points after these failed. Caching layers keep it; others pass it on.
+    fn record_route(&self, _start: Coord, _end: Coord, _summary: RouteSummary) {}
+}
+
+impl<P: RoutingProvider + ?Sized> RoutingProvider for Box<P> {
+    fn geocode(&self, query: &GeocodeQuery) -> Result<Coord, RoutingError> {
+        (**self).geocode(query)
+    }
+
+    fn reverse_geocode(&self, coord: Coord) -> Result<Vec<Coord>, RoutingError> {
+        (**self).reverse_geocode(coord)
+    }
+
+    fn is_routable(&self, coord: Coord) -> Result<bool, RoutingError> {
+        (**self).is_routable(coord)
+    }
+
+    fn get_directions(&self, start: Coord, end: Coord) -> Result<RouteSummary, RoutingError> {
+        (**self).get_directions(start, end)
+    }
+
+    fn record_route(&self, start: Coord, end: Coord, summary: RouteSummary) {
+        (**self).record_route(start, end, summary)
+    }
 }
@@ -0,0 +1,157 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+use super::cache::{Coord, GeoCache};
+use super::error::RoutingError;
+use super::geocode::GeocodeQuery;
+use super::service::RoutingProvider;
+use crate::sdk::departments::DepartmentLookup;
+
+/// What the validation pass did with a geocode outside its department.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "coord")]
+pub enum GeocodeFix {
+    /// Only reported; re-geocoding was not requested.
+    Flagged,
+    /// Re-geocoded into the right department; cached routes using the old point were dropped.
+    Replaced(Coord),
+    /// Re-geocoding failed or landed outside the department again; the entry was kept.
+    Unresolved,
+}
+
+/// A cached geocode lying outside the department its query names.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuspiciousGeocode {
+    pub query: String,
+    pub department: String,
+    pub coord: Coord,
+    /// Department the coordinate actually falls in, if any.
+    pub found_in: Option<String>,
+    pub fix: GeocodeFix,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GeocodeValidation {
+    pub checked: usize,
+    /// Entries without a department in their query, or without a loaded outline.
+    pub skipped: usize,
+    pub suspicious: Vec<SuspiciousGeocode>,
+}
+
+/// Checks every cached geocode against the outline of the department named in its query.
+/// With a provider, suspicious entries are geocoded again and replaced when the new point
+/// is inside the department. Once the quota is used up, the rest are only flagged.
+pub fn validate_geocodes(
+    cache: &Mutex<GeoCache>,
+    lookup: &DepartmentLookup,
+    mut provider: Option<&dyn RoutingProvider>,
+) -> GeocodeValidation {
+    let mut report = GeocodeValidation::default();
+    let entries: Vec<(String, Coord)> = cache
+        .lock()
+        .unwrap()
+        .geocodes()
+        .map(|(query, coord)| (query.to_string(), coord))
+        .collect();
+
+    for (query, coord) in entries {
+        let Some(department) = lookup.department_from_query(&query).cloned() else {
+            report.skipped += 1;
+            continue;
+        };
+        match lookup.contains(&department, coord) {
+            None => report.skipped += 1,
+            Some(true) => report.checked += 1,
+            Some(false) => {
+                report.checked += 1;
+                let found_in = lookup.department_at(coord).map(str::to_string);
+                log::warn!(
+                    "[VALIDATE] '{}' is cached at {:?}, outside department {} (in {})",
+                    query,
+                    coord,
+                    department,
+                    found_in.as_deref().unwrap_or("none")
+                );
+                let fix = match provider {
+                    Some(p) => regeocode(&query, coord, &department, cache, lookup, p)
+                        .unwrap_or_else(|e| {
+                            log::warn!(
+                                "Stopping re-geocoding, remaining entries are only flagged: {}",
+                                e
+                            );
+                            provider = None;
+                            GeocodeFix::Flagged
+                        }),
+                    None => GeocodeFix::Flagged,
+                };
+                report.suspicious.push(SuspiciousGeocode {
+                    query,
+                    department,
+                    coord,
+                    found_in,
+                    fix,
+                });
+            }
+        }
+    }
+
+    log::info!(
+        "Validated {} cached geocodes ({} skipped): {} outside their department",
+        report.checked,
+        report.skipped,
+        report.suspicious.len()
+    );
+    report
+}
+
+/// Geocodes `query` again. Errors only when the quota is used up.
+///
+/// The entry is dropped from the cache first, so that the cache layer of the provider
+/// stack asks its provider instead of answering with the suspicious coordinate. The old
+/// coordinate is put back unless the new one is accepted.
+fn regeocode(
+    query: &str,
+    old: Coord,
+    department: &str,
+    cache: &Mutex<GeoCache>,
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+) -> Result<GeocodeFix, RoutingError> {
+    let request = GeocodeQuery {
+        text: query.to_string(),
+        department: lookup.department_hint(department),
+    };
+    cache.lock().unwrap().remove_geocode(query);
+    let result = provider.geocode(&request);
+
+    let mut cache = cache.lock().unwrap();
+    let fix = match result {
+        Ok(coord) if lookup.contains(department, coord) == Some(true) => {
+            cache.insert_geocode(query, coord);
+            let dropped = cache.remove_routes_at(old);
+            log::info!(
+                "[VALIDATE] '{}' moved to {:?}, {} cached routes dropped",
+                query,
+                coord,
+                dropped
+            );
+            return Ok(GeocodeFix::Replaced(coord));
+        }
+        Ok(coord) => {
+            log::warn!(
+                "[VALIDATE] '{}' geocoded again to {:?}, still outside department {}",
+                query,
+                coord,
+                department
+            );
+            Ok(GeocodeFix::Unresolved)
+        }
+        Err(e) if e.is_quota_exhausted() => Err(e),
+        Err(e) => {
+            log::warn!("[VALIDATE] Could not geocode '{}' again: {}", query, e);
+            Ok(GeocodeFix::Unresolved)
+        }
+    };
+    cache.insert_geocode(query, old);
+    fix
+}
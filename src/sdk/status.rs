@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use super::departments::normalize_name;
+
+/// Words marking a cancelled or postponed event, without accents.
+const CANCELLED_WORDS: &[&str] = &[
+    "annule",
+    "annulee",
+    "annules",
+    "annulees",
+    "cancelled",
+    "canceled",
+];
+const POSTPONED_WORDS: &[&str] = &["reporte", "reportee", "reportes", "reportees", "postponed"];
+
+/// Whether an event still takes place as announced.
+///
+/// Ordered by severity, `Scheduled < Postponed < Cancelled`, so the strongest of several
+/// markers is their `max()`.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    #[default]
+    Scheduled,
+    /// Moved to a later date, which the calendar may not show yet ("REPORTÉ").
+    Postponed,
+    /// Called off, but still listed on the calendar ("ANNULÉ").
+    Cancelled,
+}
+
+impl EventStatus {
+    /// Looks for the FFE markers in a title or page text, e.g. "ANNULÉ - Open de Vitré"
+    /// or "Rapide de Dinan (reporté)". Cancellation wins when both appear.
+    pub fn from_text(text: &str) -> Self {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .map(normalize_name)
+            .filter(|w| !w.is_empty())
+            .collect();
+        let has = |keywords: &[&str]| words.iter().any(|w| keywords.contains(&w.as_str()));
+        if has(CANCELLED_WORDS) {
+            EventStatus::Cancelled
+        } else if has(POSTPONED_WORDS) {
+            EventStatus::Postponed
+        } else {
+            EventStatus::Scheduled
+        }
+    }
+
+    /// Whether the event won't happen on its listed dates.
+    pub fn is_off(&self) -> bool {
+        *self != EventStatus::Scheduled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_markers_whatever_their_case_and_accents() {
+        assert_eq!(
+            EventStatus::from_text("ANNULÉ - Open de Vitré"),
+            EventStatus::Cancelled
+        );
+        assert_eq!(
+            EventStatus::from_text("Open de Vitré (annulée)"),
+            EventStatus::Cancelled
+        );
+        assert_eq!(
+            EventStatus::from_text("Rapide de Dinan (reporté)"),
+            EventStatus::Postponed
+        );
+        assert_eq!(
+            EventStatus::from_text("REPORTEE : Blitz du club"),
+            EventStatus::Postponed
+        );
+        assert_eq!(
+            EventStatus::from_text("Postponed - Rapid"),
+            EventStatus::Postponed
+        );
+    }
+
+    #[test]
+    fn cancellation_wins_over_postponement() {
+        assert_eq!(
+            EventStatus::from_text("Reporté puis annulé"),
+            EventStatus::Cancelled
+        );
+        assert!(EventStatus::Cancelled > EventStatus::Postponed);
+        assert!(EventStatus::Postponed > EventStatus::Scheduled);
+    }
+
+    #[test]
+    fn unmarked_titles_are_scheduled() {
+        assert_eq!(
+            EventStatus::from_text("Open de Vitré"),
+            EventStatus::Scheduled
+        );
+        assert_eq!(EventStatus::from_text(""), EventStatus::Scheduled);
+        // Markers are whole words.
+        assert_eq!(
+            EventStatus::from_text("Open des Reporters"),
+            EventStatus::Scheduled
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::error::CacheError;
+use super::events::{Event, EventId};
+use super::status::EventStatus;
+
+/// An event whose status differs from the one seen by an earlier run.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusChange {
+    pub event: Event,
+    pub previous: EventStatus,
+}
+
+/// What a sync found compared to the snapshot.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncReport {
+    /// Events not seen by any earlier run.
+    pub new_events: usize,
+    pub status_changes: Vec<StatusChange>,
+}
+
+impl SyncReport {
+    pub fn log_summary(&self) {
+        if self.new_events > 0 {
+            log::info!("{} events are new since the last sync", self.new_events);
+        }
+        for change in &self.status_changes {
+            log::warn!(
+                "{} ({}, {}): {:?} -> {:?}",
+                change.event.title,
+                change.event.location,
+                change.event.start_date,
+                change.previous,
+                change.event.status
+            );
+        }
+    }
+}
+
+/// The events seen by earlier runs, keyed by their FFE id, so each run can report what
+/// changed since the last one.
+pub struct EventSnapshot {
+    path: PathBuf,
+    events: HashMap<EventId, Event>,
+}
+
+impl EventSnapshot {
+    /// Loads the snapshot file, starting empty if it doesn't exist yet. A file that can't
+    /// be parsed is an error rather than being overwritten, since every event would then
+    /// be reported as new.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CacheError> {
+        let path = path.as_ref();
+        let events = if path.exists() {
+            let data = fs::read_to_string(path).map_err(|source| CacheError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            serde_json::from_str(&data).map_err(|source| CacheError::Format {
+                path: path.to_path_buf(),
+                source,
+            })?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            events,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), CacheError> {
+        let data =
+            serde_json::to_string_pretty(&self.events).map_err(|source| CacheError::Format {
+                path: self.path.clone(),
+                source,
+            })?;
+        fs::write(&self.path, data).map_err(|source| CacheError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    /// The event last seen with this id.
+    pub fn get(&self, id: EventId) -> Option<&Event> {
+        self.events.get(&id)
+    }
+
+    /// Records freshly scraped events and reports every status change, including an
+    /// event scheduled again.
+    ///
+    /// The scraped status is taken, except when nothing was scraped about it: no marker in
+    /// the title and no detail page read this run. The status seen earlier (possibly on
+    /// the detail page) is kept then. Can be called again once more pages were read.
+    pub fn update<'a>(&mut self, events: impl IntoIterator<Item = &'a mut Event>) -> SyncReport {
+        let mut report = SyncReport::default();
+        for event in events {
+            match self.events.get(&event.id) {
+                None => report.new_events += 1,
+                Some(known) => {
+                    if !event.detail_checked && event.status == EventStatus::Scheduled {
+                        event.status = known.status;
+                    }
+                    if known.status != event.status {
+                        report.status_changes.push(StatusChange {
+                            event: event.clone(),
+                            previous: known.status,
+                        });
+                    }
+                }
+            }
+            self.events.insert(event.id, event.clone());
+        }
+        report
+    }
+}
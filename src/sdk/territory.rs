@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use super::departments::DepartmentLookup;
+use super::events::Event;
+use super::routing::{
+    error::RoutingError, geocode::GeocodeQuery, route::RouteSummary, route::get_road_distance,
+    service::RoutingProvider,
+};
+
+/// How a department can be reached, which decides how travel to it is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Territory {
+    /// Metropolitan France on the continent: by road.
+    Mainland,
+    /// 2A/2B: by road on the island, by ferry from the mainland.
+    Corsica,
+    /// 971-976: by road within the department only.
+    Overseas,
+}
+
+impl Territory {
+    pub fn of(department: &str) -> Self {
+        match department {
+            "2A" | "2B" => Territory::Corsica,
+            code if code.starts_with("97") => Territory::Overseas,
+            _ => Territory::Mainland,
+        }
+    }
+
+    /// Whether `to` can be driven to from `from`, without a ferry. An unknown origin
+    /// department is taken to be on the mainland.
+    pub fn connected_by_road(from: Option<&str>, to: &str) -> bool {
+        let from_territory = from.map_or(Territory::Mainland, Territory::of);
+        match (from_territory, Territory::of(to)) {
+            (Territory::Overseas, _) | (_, Territory::Overseas) => from == Some(to),
+            (from, to) => from == to,
+        }
+    }
+}
+
+/// The events that can be driven to from `origin_department`, for searches that don't
+/// handle ferries (day trips, group searches and tours).
+pub fn road_connected_events(origin_department: Option<&str>, events: &[Event]) -> Vec<Event> {
+    let kept: Vec<Event> = events
+        .iter()
+        .filter(|e| Territory::connected_by_road(origin_department, &e.department))
+        .cloned()
+        .collect();
+    if kept.len() < events.len() {
+        log::info!(
+            "{} events are overseas or across the sea, skipped",
+            events.len() - kept.len()
+        );
+    }
+    kept
+}
+
+/// A ferry line between a mainland port and a Corsican port, usable both ways.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FerryRoute {
+    pub mainland_port: String,
+    pub mainland_department: String,
+    pub island_port: String,
+    pub island_department: String,
+    pub crossing_hours: f64,
+}
+
+impl FerryRoute {
+    fn new(mainland: (&str, &str), island: (&str, &str), crossing_hours: f64) -> Self {
+        Self {
+            mainland_port: mainland.0.to_string(),
+            mainland_department: mainland.1.to_string(),
+            island_port: island.0.to_string(),
+            island_department: island.1.to_string(),
+            crossing_hours,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{} - {}", self.mainland_port, self.island_port)
+    }
+}
+
+/// The `[ferries]` section: crossings used to reach Corsica.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FerryConfig {
+    /// Time at the port before departure, added to every crossing.
+    pub check_in_minutes: u32,
+    pub routes: Vec<FerryRoute>,
+}
+
+impl Default for FerryConfig {
+    fn default() -> Self {
+        // Typical day crossings; check current timetables for a real trip.
+        Self {
+            check_in_minutes: 60,
+            routes: vec![
+                FerryRoute::new(("Nice", "06"), ("Bastia", "2B"), 5.5),
+                FerryRoute::new(("Nice", "06"), ("Ajaccio", "2A"), 6.0),
+                FerryRoute::new(("Toulon", "83"), ("Bastia", "2B"), 8.0),
+                FerryRoute::new(("Toulon", "83"), ("Ajaccio", "2A"), 8.0),
+                FerryRoute::new(("Marseille", "13"), ("Bastia", "2B"), 10.0),
+                FerryRoute::new(("Marseille", "13"), ("Ajaccio", "2A"), 10.0),
+            ],
+        }
+    }
+}
+
+/// A trip between the mainland and Corsica: road, crossing, road.
+#[derive(Debug, Clone, Copy)]
+pub struct FerryTrip<'a> {
+    pub ferry: &'a FerryRoute,
+    /// Road legs and crossing added up, check-in included.
+    pub total: RouteSummary,
+}
+
+/// The fastest way between `from` (mainland) and `to` (Corsica), or the reverse, over
+/// the configured ferries. Crossings whose road legs fail are skipped; a used-up quota
+/// is returned straight away.
+pub fn fastest_ferry_trip<'a>(
+    from_query: &GeocodeQuery,
+    to_query: &GeocodeQuery,
+    to_corsica: bool,
+    ferries: &'a FerryConfig,
+    lookup: &DepartmentLookup,
+    provider: &dyn RoutingProvider,
+) -> Result<FerryTrip<'a>, RoutingError> {
+    let check_in_hours = f64::from(ferries.check_in_minutes) / 60.0;
+    let mut best: Option<FerryTrip> = None;
+    let mut last_error = None;
+    for ferry in &ferries.routes {
+        let ports = (
+            lookup.build_geocode_query(&ferry.mainland_port, &ferry.mainland_department),
+            lookup.build_geocode_query(&ferry.island_port, &ferry.island_department),
+        );
+        let (Some(mainland_port), Some(island_port)) = ports else {
+            log::warn!("Ferry {} has an unknown department, skipped", ferry.name());
+            continue;
+        };
+        let (departure_port, arrival_port) = if to_corsica {
+            (mainland_port, island_port)
+        } else {
+            (island_port, mainland_port)
+        };
+        let legs = get_road_distance(from_query, &departure_port, provider).and_then(|first| {
+            get_road_distance(&arrival_port, to_query, provider).map(|last| (first, last))
+        });
+        let (first, last) = match legs {
+            Ok(legs) => legs,
+            Err(e) if e.is_quota_exhausted() => return Err(e),
+            Err(e) => {
+                log::debug!("No road legs via ferry {}: {}", ferry.name(), e);
+                last_error = Some(e);
+                continue;
+            }
+        };
+        let total = RouteSummary {
+            distance_km: first.distance_km + last.distance_km,
+            duration_hours: first.duration_hours
+                + check_in_hours
+                + ferry.crossing_hours
+                + last.duration_hours,
+        };
+        if best.is_none_or(|b| total.duration_hours < b.total.duration_hours) {
+            best = Some(FerryTrip { ferry, total });
+        }
+    }
+    best.ok_or_else(|| {
+        last_error.unwrap_or_else(|| RoutingError::Generic("No ferry route configured".to_string()))
+    })
+}
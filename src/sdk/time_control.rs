@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+use super::details::{Cadence, EventDetail};
+
+/// Title words naming a time control, lowercase.
+const BLITZ_WORDS: &[&str] = &["blitz", "bullet"];
+const RAPID_WORDS: &[&str] = &["rapide", "rapides", "rapid", "semi", "actif", "actifs"];
+const CLASSICAL_WORDS: &[&str] = &["lent", "lente", "lentes", "classique", "standard"];
+
+/// The kind of games played at an event, by FIDE thresholds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeControl {
+    /// 60 minutes or more per player for 60 moves ("cadence lente").
+    Classical,
+    /// More than 10 and less than 60 minutes.
+    Rapid,
+    /// 10 minutes or less.
+    Blitz,
+    /// Several kinds, e.g. a rapid and a blitz on the same weekend. Holds at least two
+    /// single kinds, in the order above, never `Mixed` or `Unknown`.
+    Mixed(Vec<TimeControl>),
+    #[default]
+    Unknown,
+}
+
+impl TimeControl {
+    /// Guesses the time control from words in an event title, e.g.
+    /// "Championnat Bretagne TC 2025 Rapide" or "Blitz du club".
+    pub fn from_title(title: &str) -> Self {
+        let title = title.to_lowercase();
+        let words: Vec<&str> = title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        let has = |keywords: &[&str]| words.iter().any(|w| keywords.contains(w));
+        let mut found = Vec::new();
+        if has(BLITZ_WORDS) {
+            found.push(TimeControl::Blitz);
+        }
+        if has(RAPID_WORDS) {
+            found.push(TimeControl::Rapid);
+        }
+        if has(CLASSICAL_WORDS) {
+            found.push(TimeControl::Classical);
+        }
+        Self::combine(found)
+    }
+
+    /// Classifies a single cadence by the time each player has for 60 moves.
+    pub fn from_cadence(cadence: &Cadence) -> Self {
+        let minutes = cadence.base_minutes + cadence.increment_seconds;
+        if minutes >= 60.0 {
+            TimeControl::Classical
+        } else if minutes > 10.0 {
+            TimeControl::Rapid
+        } else {
+            TimeControl::Blitz
+        }
+    }
+
+    /// Classifies the cadence field of a detail page, which may list several cadences
+    /// ("Rapide : 15 min + 3 s / Blitz : 3 min + 2 s").
+    pub fn from_detail(detail: &EventDetail) -> Self {
+        let Some(text) = detail.cadence.as_deref() else {
+            return TimeControl::Unknown;
+        };
+        let found = text
+            .split([';', '/'])
+            .filter_map(|part| {
+                // Skip a leading label such as "Rapide :".
+                let part = part.rsplit_once(':').map_or(part, |(_, value)| value);
+                Cadence::parse(part.trim())
+            })
+            .map(|cadence| Self::from_cadence(&cadence))
+            .collect();
+        Self::combine(found)
+    }
+
+    /// Whether a lowercase title word names a time control.
+    pub fn is_keyword(word: &str) -> bool {
+        [BLITZ_WORDS, RAPID_WORDS, CLASSICAL_WORDS]
+            .iter()
+            .any(|words| words.contains(&word))
+    }
+
+    /// The time control of several events together: theirs if they agree, else the
+    /// mix of every kind found.
+    pub fn combine(found: Vec<TimeControl>) -> Self {
+        let mut kinds: Vec<TimeControl> = found
+            .into_iter()
+            .flat_map(|tc| match tc {
+                TimeControl::Mixed(kinds) => kinds,
+                TimeControl::Unknown => Vec::new(),
+                single => vec![single],
+            })
+            .collect();
+        kinds.sort_by_key(TimeControl::rank);
+        kinds.dedup();
+        match kinds.len() {
+            0 => TimeControl::Unknown,
+            1 => kinds.remove(0),
+            _ => TimeControl::Mixed(kinds),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            TimeControl::Classical => 0,
+            TimeControl::Rapid => 1,
+            TimeControl::Blitz => 2,
+            TimeControl::Mixed(_) => 3,
+            TimeControl::Unknown => 4,
+        }
+    }
+
+    /// Whether an event of this kind passes a filter on `wanted`: a mixed event passes
+    /// when one of its kinds is wanted. Unknown events are kept, since nothing says they
+    /// don't match.
+    pub fn matches(&self, wanted: &[TimeControl]) -> bool {
+        match self {
+            _ if wanted.is_empty() => true,
+            TimeControl::Unknown => true,
+            TimeControl::Mixed(kinds) => kinds.iter().any(|kind| wanted.contains(kind)),
+            single => wanted.contains(single),
+        }
+    }
+}
+
+impl std::str::FromStr for TimeControl {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "classical" | "classic" | "standard" | "lent" => Ok(TimeControl::Classical),
+            "rapid" | "rapide" => Ok(TimeControl::Rapid),
+            "blitz" => Ok(TimeControl::Blitz),
+            other => Err(format!("Unknown time control: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeControl::*;
+    use super::*;
+
+    fn detail(cadence: &str) -> EventDetail {
+        EventDetail {
+            cadence: Some(cadence.to_string()),
+            ..EventDetail::default()
+        }
+    }
+
+    #[test]
+    fn reads_time_control_words_in_titles() {
+        assert_eq!(
+            TimeControl::from_title("Championnat Bretagne TC 2025 Rapide"),
+            Rapid
+        );
+        assert_eq!(TimeControl::from_title("BULLET de Noël"), Blitz);
+        assert_eq!(TimeControl::from_title("Open cadence lente"), Classical);
+        assert_eq!(TimeControl::from_title("Tournoi semi-rapide"), Rapid);
+        assert_eq!(TimeControl::from_title("Open de Vitré"), Unknown);
+        // Whole words only.
+        assert_eq!(TimeControl::from_title("Blitzkrieg"), Unknown);
+    }
+
+    #[test]
+    fn titles_naming_several_kinds_are_mixed() {
+        assert_eq!(
+            TimeControl::from_title("Rapide et Blitz de Dinan"),
+            Mixed(vec![Rapid, Blitz])
+        );
+        assert_eq!(
+            TimeControl::from_title("Lent, rapide & blitz"),
+            Mixed(vec![Classical, Rapid, Blitz])
+        );
+    }
+
+    #[test]
+    fn classifies_detail_page_cadences() {
+        assert_eq!(
+            TimeControl::from_detail(&detail("90 min + 30 s")),
+            Classical
+        );
+        assert_eq!(TimeControl::from_detail(&detail("3 min + 2 s")), Blitz);
+        // 10 minutes + 5 s per move is 15 minutes for 60 moves.
+        assert_eq!(TimeControl::from_detail(&detail("10 min + 5 s")), Rapid);
+        assert_eq!(
+            TimeControl::from_detail(&detail("Rapide : 15 min + 3 s / Blitz : 3 min + 2 s")),
+            Mixed(vec![Rapid, Blitz])
+        );
+        assert_eq!(TimeControl::from_detail(&EventDetail::default()), Unknown);
+    }
+
+    #[test]
+    fn combine_flattens_and_orders_kinds() {
+        assert_eq!(TimeControl::combine(vec![]), Unknown);
+        assert_eq!(TimeControl::combine(vec![Unknown, Rapid]), Rapid);
+        assert_eq!(TimeControl::combine(vec![Blitz, Blitz]), Blitz);
+        assert_eq!(
+            TimeControl::combine(vec![Blitz, Classical]),
+            Mixed(vec![Classical, Blitz])
+        );
+        assert_eq!(
+            TimeControl::combine(vec![Mixed(vec![Rapid, Blitz]), Rapid, Classical]),
+            Mixed(vec![Classical, Rapid, Blitz])
+        );
+    }
+
+    #[test]
+    fn filter_keeps_unknown_and_partly_matching_events() {
+        assert!(Rapid.matches(&[]));
+        assert!(Rapid.matches(&[Rapid]));
+        assert!(!Rapid.matches(&[Blitz]));
+        assert!(Unknown.matches(&[Blitz]));
+
+        let rapid_and_blitz = Mixed(vec![Rapid, Blitz]);
+        assert!(rapid_and_blitz.matches(&[Blitz]));
+        assert!(!rapid_and_blitz.matches(&[Classical]));
+    }
+}
@@ -1,2 +1,4 @@
 pub mod log;
+pub mod quota;
 pub mod rate_limit;
+pub mod retry;
@@ -0,0 +1,239 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use crate::sdk::error::CacheError;
+use crate::sdk::routing::error::RoutingError;
+
+/// Public ORS endpoints with their own daily quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrsEndpoint {
+    Directions,
+    Geocode,
+    ReverseGeocode,
+    Matrix,
+}
+
+impl fmt::Display for OrsEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OrsEndpoint::Directions => "directions",
+            OrsEndpoint::Geocode => "geocode",
+            OrsEndpoint::ReverseGeocode => "reverse_geocode",
+            OrsEndpoint::Matrix => "matrix",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Daily limits of the public ORS plan, and how many calls to keep in reserve.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Track quotas at all (only applies to the remote API).
+    pub enabled: bool,
+    /// Stop once this many calls or fewer are left, leaving room for manual use.
+    pub reserve: u32,
+    pub directions: u32,
+    pub geocode: u32,
+    pub reverse_geocode: u32,
+    pub matrix: u32,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            reserve: 0,
+            directions: 2000,
+            geocode: 1000,
+            reverse_geocode: 1000,
+            matrix: 500,
+        }
+    }
+}
+
+impl QuotaConfig {
+    pub fn limit(&self, endpoint: OrsEndpoint) -> u32 {
+        match endpoint {
+            OrsEndpoint::Directions => self.directions,
+            OrsEndpoint::Geocode => self.geocode,
+            OrsEndpoint::ReverseGeocode => self.reverse_geocode,
+            OrsEndpoint::Matrix => self.matrix,
+        }
+    }
+}
+
+/// Calls made in the current window, persisted between runs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct EndpointUsage {
+    used: u32,
+    resets_at: DateTime<Utc>,
+}
+
+impl EndpointUsage {
+    fn fresh(now: DateTime<Utc>) -> Self {
+        Self {
+            used: 0,
+            resets_at: now + TimeDelta::days(1),
+        }
+    }
+}
+
+/// Changes counted in memory before the usage file is rewritten.
+const SAVE_EVERY: u32 = 20;
+
+/// Counts calls against the daily ORS quotas, in a JSON file shared by every run.
+///
+/// Each call is counted before it is sent. When the server reports its own count in the
+/// `x-ratelimit-*` headers, that count wins. The file is rewritten every `SAVE_EVERY`
+/// changes, as soon as a quota runs out, and when the tracker is dropped.
+pub struct QuotaTracker {
+    path: PathBuf,
+    config: QuotaConfig,
+    usage: Mutex<HashMap<OrsEndpoint, EndpointUsage>>,
+    /// Changes not yet written to `path`.
+    unsaved: AtomicU32,
+}
+
+impl QuotaTracker {
+    /// Loads the usage file, starting from zero if it doesn't exist yet.
+    pub fn load<P: AsRef<Path>>(path: P, config: QuotaConfig) -> Result<Self, CacheError> {
+        let path = path.as_ref();
+        let usage = if path.exists() {
+            let data = fs::read_to_string(path).map_err(|source| CacheError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            serde_json::from_str(&data).map_err(|source| CacheError::Format {
+                path: path.to_path_buf(),
+                source,
+            })?
+        } else {
+            HashMap::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            config,
+            usage: Mutex::new(usage),
+            unsaved: AtomicU32::new(0),
+        })
+    }
+
+    pub fn save(&self) -> Result<(), CacheError> {
+        let usage = self.usage.lock().unwrap();
+        self.write(&usage)?;
+        self.unsaved.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn write(&self, usage: &HashMap<OrsEndpoint, EndpointUsage>) -> Result<(), CacheError> {
+        let data = serde_json::to_string_pretty(usage).map_err(|source| CacheError::Format {
+            path: self.path.clone(),
+            source,
+        })?;
+        fs::write(&self.path, data).map_err(|source| CacheError::Io {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    /// Records one change, writing the file when `now` is set or enough changes piled up.
+    fn persist(&self, usage: &HashMap<OrsEndpoint, EndpointUsage>, now: bool) {
+        let unsaved = self.unsaved.fetch_add(1, Ordering::Relaxed) + 1;
+        if !now && unsaved < SAVE_EVERY {
+            return;
+        }
+        match self.write(usage) {
+            Ok(()) => self.unsaved.store(0, Ordering::Relaxed),
+            Err(e) => log::warn!("Could not save ORS quota usage: {}", e),
+        }
+    }
+
+    /// Calls left today for `endpoint`.
+    pub fn remaining(&self, endpoint: OrsEndpoint) -> u32 {
+        let now = Utc::now();
+        let usage = self.usage.lock().unwrap();
+        let used = usage
+            .get(&endpoint)
+            .filter(|u| u.resets_at > now)
+            .map_or(0, |u| u.used);
+        self.config.limit(endpoint).saturating_sub(used)
+    }
+
+    /// Counts one call, or refuses it when only the reserve is left.
+    pub fn acquire(&self, endpoint: OrsEndpoint) -> Result<(), RoutingError> {
+        let now = Utc::now();
+        let mut usage = self.usage.lock().unwrap();
+        let entry = usage
+            .entry(endpoint)
+            .or_insert_with(|| EndpointUsage::fresh(now));
+        if entry.resets_at <= now {
+            *entry = EndpointUsage::fresh(now);
+        }
+        let remaining = self.config.limit(endpoint).saturating_sub(entry.used);
+        if remaining <= self.config.reserve {
+            return Err(RoutingError::DailyQuotaExhausted {
+                endpoint,
+                resets_at: entry.resets_at,
+            });
+        }
+        entry.used += 1;
+        let exhausted = remaining - 1 <= self.config.reserve;
+        self.persist(&usage, exhausted);
+        Ok(())
+    }
+
+    /// Aligns the local count with the server's `x-ratelimit-remaining`/`-reset` headers.
+    pub fn sync(&self, endpoint: OrsEndpoint, remaining: u32, reset_in: Option<Duration>) {
+        let now = Utc::now();
+        let mut usage = self.usage.lock().unwrap();
+        let entry = usage
+            .entry(endpoint)
+            .or_insert_with(|| EndpointUsage::fresh(now));
+        let before = *entry;
+        entry.used = self.config.limit(endpoint).saturating_sub(remaining);
+        if let Some(reset_in) = reset_in.and_then(|d| TimeDelta::from_std(d).ok()) {
+            entry.resets_at = now + reset_in;
+        }
+        // The reset time is recomputed from "now" on every response; only a real move counts.
+        let moved = (entry.resets_at - before.resets_at).abs() > TimeDelta::minutes(1);
+        if entry.used != before.used || moved {
+            self.persist(&usage, remaining <= self.config.reserve);
+        }
+    }
+
+    /// Logs the calls left for every endpoint.
+    pub fn log_summary(&self) {
+        for endpoint in [
+            OrsEndpoint::Directions,
+            OrsEndpoint::Geocode,
+            OrsEndpoint::ReverseGeocode,
+            OrsEndpoint::Matrix,
+        ] {
+            log::info!(
+                "ORS {} quota: {}/{} calls left today",
+                endpoint,
+                self.remaining(endpoint),
+                self.config.limit(endpoint)
+            );
+        }
+    }
+}
+
+impl Drop for QuotaTracker {
+    fn drop(&mut self) {
+        if *self.unsaved.get_mut() > 0
+            && let Err(e) = self.save()
+        {
+            log::warn!("Could not save ORS quota usage: {}", e);
+        }
+    }
+}
@@ -1,32 +1,200 @@
-use governor::clock::DefaultClock;
+use governor::clock::{Clock, DefaultClock};
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
 use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
 
-/// A synchronous wrapper around the governor RateLimiter.
+type DirectLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+
+/// What a limit applies to: a host, optionally narrowed to one endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LimitKey {
+    pub host: String,
+    pub endpoint: Option<String>,
+}
+
+impl LimitKey {
+    pub fn new(host: impl Into<String>, endpoint: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            endpoint: Some(endpoint.into()),
+        }
+    }
+
+    /// A key covering every endpoint of `host`.
+    pub fn host(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            endpoint: None,
+        }
+    }
+
+    /// Parses the `"host"` or `"host/endpoint"` form used in the config file.
+    pub fn parse(key: &str) -> Self {
+        match key.split_once('/') {
+            Some((host, endpoint)) => Self::new(host, endpoint),
+            None => Self::host(key),
+        }
+    }
+}
+
+impl fmt::Display for LimitKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.endpoint {
+            Some(endpoint) => write!(f, "{}/{}", self.host, endpoint),
+            None => f.write_str(&self.host),
+        }
+    }
+}
+
+/// One rate limit: a sustained rate, plus how many calls may go out back to back.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    /// Defaults to `per_minute`, i.e. a full minute's worth of calls at once.
+    pub burst: Option<u32>,
+}
+
+impl RateLimit {
+    pub fn per_minute(calls: u32) -> Self {
+        Self {
+            per_minute: calls,
+            burst: None,
+        }
+    }
+
+    fn quota(&self) -> Quota {
+        let rate = NonZeroU32::new(self.per_minute).unwrap_or(NonZeroU32::MIN);
+        let burst = self.burst.and_then(NonZeroU32::new).unwrap_or(rate);
+        Quota::per_minute(rate).allow_burst(burst)
+    }
+}
+
+/// The `[rate_limit]` section: a default limit and per-host/endpoint overrides.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Calls per minute for keys without their own entry.
+    pub per_minute: u32,
+    pub burst: Option<u32>,
+    /// Keyed by `"host"` or `"host/endpoint"`, e.g. `"api.openrouteservice.org/geocode"`.
+    pub endpoints: HashMap<String, RateLimit>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        // Public ORS plan: 40 directions/minute, 100 geocoding calls/minute.
+        let endpoints = [
+            (
+                "api.openrouteservice.org/geocode",
+                RateLimit::per_minute(100),
+            ),
+            (
+                "api.openrouteservice.org/reverse_geocode",
+                RateLimit::per_minute(100),
+            ),
+            (
+                "www.echecs.asso.fr",
+                RateLimit {
+                    per_minute: 60,
+                    burst: Some(5),
+                },
+            ),
+        ];
+        Self {
+            per_minute: 40,
+            burst: None,
+            endpoints: endpoints
+                .into_iter()
+                .map(|(key, limit)| (key.to_string(), limit))
+                .collect(),
+        }
+    }
+}
+
+/// Rate limiter with one bucket per host/endpoint, shared by the ORS providers and the
+/// FFE scraper. Cloning shares the buckets.
+#[derive(Clone)]
 pub struct Limiter {
-    limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    default: RateLimit,
+    limits: HashMap<LimitKey, RateLimit>,
+    buckets: Mutex<HashMap<LimitKey, Arc<DirectLimiter>>>,
 }
 
 impl Limiter {
-    /// Creates a new limiter configured for ORS (40 calls/minute).
+    /// Creates a new limiter configured for the public ORS plan.
     pub fn new() -> Self {
-        let quota = Quota::per_minute(NonZeroU32::new(40).unwrap());
+        Self::from_config(&RateLimitConfig::default())
+    }
+
+    /// Creates a limiter allowing `calls` per minute (at least one) for every key.
+    pub fn per_minute(calls: u32) -> Self {
+        Self::with_limits(RateLimit::per_minute(calls), HashMap::new())
+    }
+
+    /// Builds the limiter described by a `[rate_limit]` section.
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        let default = RateLimit {
+            per_minute: config.per_minute,
+            burst: config.burst,
+        };
+        let limits = config
+            .endpoints
+            .iter()
+            .map(|(key, limit)| (LimitKey::parse(key), *limit))
+            .collect();
+        Self::with_limits(default, limits)
+    }
+
+    fn with_limits(default: RateLimit, limits: HashMap<LimitKey, RateLimit>) -> Self {
         Self {
-            limiter: Arc::new(RateLimiter::direct(quota)),
+            inner: Arc::new(Inner {
+                default,
+                limits,
+                buckets: Mutex::new(HashMap::new()),
+            }),
         }
     }
 
-    /// Blocks the current thread until a cell is available.
-    pub fn wait(&self) {
-        while self.limiter.check().is_err() {
-            // Sleep briefly to avoid a spin-loop.
-            thread::sleep(Duration::from_millis(20));
+    /// The bucket for `key`: its own limit, else its host's, else the default.
+    /// A host-wide limit is one bucket shared by all of the host's endpoints.
+    fn bucket(&self, key: &LimitKey) -> Arc<DirectLimiter> {
+        let host_key = LimitKey::host(key.host.clone());
+        let (bucket_key, limit) = match self.inner.limits.get(key) {
+            Some(limit) => (key.clone(), *limit),
+            None => match self.inner.limits.get(&host_key) {
+                Some(limit) => (host_key, *limit),
+                None => (key.clone(), self.inner.default),
+            },
+        };
+        let mut buckets = self.inner.buckets.lock().unwrap();
+        buckets
+            .entry(bucket_key)
+            .or_insert_with(|| Arc::new(RateLimiter::direct(limit.quota())))
+            .clone()
+    }
+
+    /// Blocks the current thread until a call to `key` is allowed.
+    pub fn wait(&self, key: &LimitKey) {
+        let bucket = self.bucket(key);
+        let clock = DefaultClock::default();
+        while let Err(not_until) = bucket.check() {
+            thread::sleep(not_until.wait_time_from(clock.now()));
         }
     }
+
+    /// Waits asynchronously until a call to `key` is allowed.
+    pub async fn until_ready(&self, key: &LimitKey) {
+        self.bucket(key).until_ready().await;
+    }
 }
 
 impl Default for Limiter {
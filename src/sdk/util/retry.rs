@@ -0,0 +1,201 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::sdk::routing::error::{RoutingError, RoutingErrorKind};
+
+/// Exponential backoff with jitter, shared by the ORS providers, the FFE scraper and the
+/// commune API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one. 1 disables retries.
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    /// Upper bound for a single wait; a server asking for longer is not waited for.
+    pub max_delay_ms: u64,
+    /// Randomise each delay between half and all of its value.
+    pub jitter: bool,
+    /// Which routing errors are retried. Unset, those `RoutingError::is_retryable`
+    /// allows: rate limits, 500/502/503/504, timeouts and dropped connections.
+    pub retry_on: Option<HashSet<RoutingErrorKind>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+            jitter: true,
+            retry_on: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the configured policy retries this routing error.
+    pub fn retries(&self, error: &RoutingError) -> bool {
+        match &self.retry_on {
+            Some(kinds) => kinds.contains(&error.kind()),
+            None => error.is_retryable(),
+        }
+    }
+
+    /// Backoff before attempt `attempt + 1` (attempts are counted from 1).
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(1u64 << (attempt - 1).min(16))
+            .min(self.max_delay_ms);
+        let delay = if self.jitter {
+            fastrand::u64(exponential / 2..=exponential)
+        } else {
+            exponential
+        };
+        Duration::from_millis(delay)
+    }
+
+    /// Runs `op` until it succeeds, fails with an error `should_retry` rejects, or
+    /// runs out of attempts. `retry_after` extracts a server-requested delay, which
+    /// replaces the backoff when present.
+    pub fn run<T, E: Display>(
+        &self,
+        what: &str,
+        should_retry: impl Fn(&E) -> bool,
+        retry_after: impl Fn(&E) -> Option<Duration>,
+        mut op: impl FnMut() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_attempts && should_retry(&e) => {
+                    let delay = retry_after(&e).unwrap_or_else(|| self.backoff(attempt));
+                    if delay > Duration::from_millis(self.max_delay_ms) {
+                        log::warn!(
+                            "{} failed ({}); server asks to wait {:?}, giving up",
+                            what,
+                            e,
+                            delay
+                        );
+                        return Err(e);
+                    }
+                    log::warn!(
+                        "{} failed (attempt {}/{}): {}. Retrying in {:?}",
+                        what,
+                        attempt,
+                        self.max_attempts,
+                        e,
+                        delay
+                    );
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// `run` for routing calls, using `retry_on` and the error's rate-limit hint.
+    pub fn run_routing<T>(
+        &self,
+        what: &str,
+        op: impl FnMut() -> Result<T, RoutingError>,
+    ) -> Result<T, RoutingError> {
+        self.run(what, |e| self.retries(e), RoutingError::retry_after, op)
+    }
+}
+
+/// Rate-limit information sent by ORS (and standard HTTP) response headers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitInfo {
+    /// `Retry-After`, in seconds.
+    pub retry_after: Option<Duration>,
+    /// `x-ratelimit-remaining`: calls left in the current window.
+    pub remaining: Option<u32>,
+    /// `x-ratelimit-reset`: time until the window resets.
+    pub reset_in: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+        };
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let remaining = header("x-ratelimit-remaining").and_then(|v| v.parse().ok());
+        let reset_in = header("x-ratelimit-reset")
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(reset_to_duration);
+        Self {
+            retry_after,
+            remaining,
+            reset_in,
+        }
+    }
+
+    /// How long to wait before the next call: the explicit `Retry-After`, or the
+    /// reset time once the window's calls are used up.
+    pub fn wait_hint(&self) -> Option<Duration> {
+        self.retry_after
+            .or_else(|| self.reset_in.filter(|_| self.remaining == Some(0)))
+    }
+}
+
+/// `x-ratelimit-reset` is a Unix timestamp on ORS, but some proxies send seconds.
+fn reset_to_duration(value: u64) -> Duration {
+    const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+    if value >= EPOCH_THRESHOLD {
+        let reset = UNIX_EPOCH + Duration::from_secs(value);
+        reset
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO)
+    } else {
+        Duration::from_secs(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_on_replaces_the_default_set() {
+        let too_long = RoutingError::RouteTooLong {
+            message: String::new(),
+        };
+        let rate_limited = RoutingError::RateLimited {
+            message: String::new(),
+            retry_after: None,
+        };
+
+        let default = RetryPolicy::default();
+        assert!(default.retries(&rate_limited));
+        assert!(!default.retries(&too_long));
+
+        let policy: RetryPolicy = toml::from_str(r#"retry_on = ["route_too_long"]"#).unwrap();
+        assert!(policy.retries(&too_long));
+        assert!(!policy.retries(&rate_limited));
+
+        assert!(toml::from_str::<RetryPolicy>(r#"retry_on = ["sometimes"]"#).is_err());
+    }
+}